@@ -0,0 +1,16 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+use trace::prelude::*;
+
+/// Renders a YAML scene, such as the book's `cover.yml`:
+/// `cargo run --release --example scene -- cover.yml`
+fn main() -> Result<(), Box<dyn Error>> {
+    let path = env::args().nth(1).ok_or("usage: scene <file.yml>")?;
+    let (camera, world) = load_scene(&fs::read_to_string(&path)?)?;
+
+    let canvas = camera.render(&world);
+
+    canvas.write("scene.ppm")?;
+    Ok(())
+}
@@ -0,0 +1,220 @@
+use crate::mat4::Mat4;
+use crate::point::Point;
+use crate::ray::Ray;
+
+/// An axis-aligned box, from `min` to `max`, enclosing a shape.
+/// Extents may be infinite, like those of a plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+
+    /// A box enclosing nothing, growing to fit
+    /// whatever is added to it.
+    pub fn empty() -> Self {
+        Self {
+            min: Point {
+                x: f64::INFINITY,
+                y: f64::INFINITY,
+                z: f64::INFINITY,
+            },
+            max: Point {
+                x: f64::NEG_INFINITY,
+                y: f64::NEG_INFINITY,
+                z: f64::NEG_INFINITY,
+            },
+        }
+    }
+
+    /// Grows the box to enclose `point`.
+    pub fn add_point(&mut self, point: Point) {
+        self.min.x = self.min.x.min(point.x);
+        self.min.y = self.min.y.min(point.y);
+        self.min.z = self.min.z.min(point.z);
+        self.max.x = self.max.x.max(point.x);
+        self.max.y = self.max.y.max(point.y);
+        self.max.z = self.max.z.max(point.z);
+    }
+
+    /// Grows the box to enclose `other`.
+    pub fn add_box(&mut self, other: BoundingBox) {
+        self.add_point(other.min);
+        self.add_point(other.max);
+    }
+
+    /// Returns the box enclosing the eight corners of this one,
+    /// once transformed by `transform`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// # use std::f64::consts::FRAC_PI_4;
+    /// let bounds = BoundingBox::new(point![-1, -1, -1], point![1, 1, 1]);
+    /// let transform = Mat4::identity().rotate_y(FRAC_PI_4).rotate_x(FRAC_PI_4);
+    /// let bounds = bounds.transform(&transform);
+    /// assert_eq!(bounds.min, point![-1.41421, -1.70711, -1.70711]);
+    /// assert_eq!(bounds.max, point![1.41421, 1.70711, 1.70711]);
+    /// ```
+    pub fn transform(&self, transform: &Mat4) -> Self {
+        let mut bounds = Self::empty();
+        for x in [self.min.x, self.max.x] {
+            for y in [self.min.y, self.max.y] {
+                for z in [self.min.z, self.max.z] {
+                    bounds.add_point(transform_corner(transform, [x, y, z]));
+                }
+            }
+        }
+        bounds
+    }
+
+    /// Checks whether `ray` crosses the box, using the slab method.
+    /// A box lying entirely behind the origin of the ray is missed.
+    pub fn intersects(&self, ray: &Ray) -> bool {
+        let (xtmin, xtmax) = check_axis(ray.origin.x, ray.direction.x, self.min.x, self.max.x);
+        let (ytmin, ytmax) = check_axis(ray.origin.y, ray.direction.y, self.min.y, self.max.y);
+        let (ztmin, ztmax) = check_axis(ray.origin.z, ray.direction.z, self.min.z, self.max.z);
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+        tmin <= tmax && tmax >= 0.0
+    }
+}
+
+/// Multiplies a corner by `transform`, leaving out the terms whose
+/// coefficient is zero so infinite extents don't turn into NaN.
+fn transform_corner(transform: &Mat4, corner: [f64; 3]) -> Point {
+    let mut result = [0.0; 3];
+    for (row, value) in result.iter_mut().enumerate() {
+        *value = transform[(row, 3)];
+        for (col, coordinate) in corner.iter().enumerate() {
+            let coefficient = transform[(row, col)];
+            if coefficient != 0.0 {
+                *value += coefficient * coordinate;
+            }
+        }
+    }
+    Point {
+        x: result[0],
+        y: result[1],
+        z: result[2],
+    }
+}
+
+/// Returns the `t` values where the ray crosses
+/// the two planes of a slab, at `min` and `max`.
+fn check_axis(origin: f64, direction: f64, min: f64, max: f64) -> (f64, f64) {
+    let tmin = (min - origin) / direction;
+    let tmax = (max - origin) / direction;
+    if tmin.is_nan() || tmax.is_nan() {
+        // the ray lies within an infinite slab, or on its boundary
+        (f64::NEG_INFINITY, f64::INFINITY)
+    } else if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::Vector;
+    use crate::{point, vector};
+
+    #[test]
+    fn test_add_point() {
+        // Adding points to an empty bounding box
+        let mut bounds = BoundingBox::empty();
+        bounds.add_point(point![-5, 2, 0]);
+        bounds.add_point(point![7, 0, -3]);
+        assert_eq!(bounds.min, point![-5, 0, -3]);
+        assert_eq!(bounds.max, point![7, 2, 0]);
+
+        // Adding one bounding box to another
+        let mut bounds = BoundingBox::new(point![-5, -2, 0], point![7, 4, 4]);
+        bounds.add_box(BoundingBox::new(point![8, -7, -2], point![14, 2, 8]));
+        assert_eq!(bounds.min, point![-5, -7, -2]);
+        assert_eq!(bounds.max, point![14, 4, 8]);
+    }
+
+    #[test]
+    fn test_transform() {
+        // Transforming an infinite bounding box keeps it free of NaN
+        let bounds = BoundingBox::new(
+            point![f64::NEG_INFINITY, 0, f64::NEG_INFINITY],
+            point![f64::INFINITY, 0, f64::INFINITY],
+        );
+        let bounds = bounds.transform(&Mat4::identity().translate(0, 2, 0));
+        assert_eq!(bounds.min.y, 2.0);
+        assert_eq!(bounds.max.y, 2.0);
+        assert_eq!(bounds.min.x, f64::NEG_INFINITY);
+        assert_eq!(bounds.max.z, f64::INFINITY);
+    }
+
+    #[test]
+    fn test_intersects() {
+        // Intersecting a ray with a bounding box at the origin
+        let bounds = BoundingBox::new(point![-1, -1, -1], point![1, 1, 1]);
+        let cases = [
+            (point![5, 0.5, 0], vector![-1, 0, 0], true),
+            (point![-5, 0.5, 0], vector![1, 0, 0], true),
+            (point![0.5, 5, 0], vector![0, -1, 0], true),
+            (point![0.5, -5, 0], vector![0, 1, 0], true),
+            (point![0.5, 0, 5], vector![0, 0, -1], true),
+            (point![0.5, 0, -5], vector![0, 0, 1], true),
+            (point![0, 0.5, 0], vector![0, 0, 1], true),
+            (point![-2, 0, 0], vector![2, 4, 6], false),
+            (point![0, -2, 0], vector![6, 2, 4], false),
+            (point![0, 0, -2], vector![4, 6, 2], false),
+            (point![2, 0, 2], vector![0, 0, -1], false),
+            (point![0, 2, 2], vector![0, -1, 0], false),
+            (point![2, 2, 0], vector![-1, 0, 0], false),
+        ];
+        for (origin, direction, result) in cases {
+            let ray = Ray {
+                origin,
+                direction: direction.normalize(),
+            };
+            assert_eq!(bounds.intersects(&ray), result);
+        }
+
+        // Intersecting a ray with a non-cubic bounding box
+        let bounds = BoundingBox::new(point![5, -2, 0], point![11, 4, 7]);
+        let cases = [
+            (point![15, 1, 2], vector![-1, 0, 0], true),
+            (point![-5, -1, 4], vector![1, 0, 0], true),
+            (point![7, 6, 5], vector![0, -1, 0], true),
+            (point![9, -5, 6], vector![0, 1, 0], true),
+            (point![8, 2, 12], vector![0, 0, -1], true),
+            (point![6, 0, -5], vector![0, 0, 1], true),
+            (point![8, 1, 3.5], vector![0, 0, 1], true),
+            (point![9, -1, -8], vector![2, 4, 6], false),
+            (point![8, 3, -4], vector![6, 2, 4], false),
+            (point![9, -1, -2], vector![4, 6, 2], false),
+            (point![4, 0, 9], vector![0, 0, -1], false),
+            (point![8, 6, -1], vector![0, -1, 0], false),
+            (point![12, 5, 4], vector![-1, 0, 0], false),
+        ];
+        for (origin, direction, result) in cases {
+            let ray = Ray {
+                origin,
+                direction: direction.normalize(),
+            };
+            assert_eq!(bounds.intersects(&ray), result);
+        }
+
+        // A ray pointing away from a bounding box misses it
+        let bounds = BoundingBox::new(point![-1, -1, -1], point![1, 1, 1]);
+        let ray = Ray {
+            origin: point![0, 0, -5],
+            direction: vector![0, 0, -1],
+        };
+        assert!(!bounds.intersects(&ray));
+    }
+}
@@ -1,17 +1,69 @@
 use crate::canvas::Canvas;
+use crate::color::Color;
+use crate::intersection::Intersection;
 use crate::mat4::Mat4;
 use crate::point::Point;
 use crate::ray::Ray;
+use crate::stats::{self, RenderStats};
+use crate::vector::Vector;
 use crate::world::World;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::ops::Range;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
 
+/// Position of a pixel, with its color and alpha.
+type RenderedPixel = (usize, usize, (Color, f64));
+
+/// Pixels of a row, with the work done to render them.
+type RenderedRow = (Vec<RenderedPixel>, RenderStats);
+
+/// How sub-samples are placed within a pixel, see `Camera::samples`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Sampling {
+    /// Sub-samples at the center of each cell of a regular grid.
+    #[default]
+    Regular,
+    /// Sub-samples at a random position within each cell of a regular grid.
+    Jittered,
+}
+
+/// How rays leave the camera, see `Camera::with_projection`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Projection {
+    /// Rays spread from a single point, through a view plane
+    /// `field_of_view` radians wide along its longer side.
+    Perspective { field_of_view: f64 },
+    /// Rays are parallel, starting across a view plane of at least
+    /// `width` by `height`, so distant objects keep their size.
+    Orthographic { width: f64, height: f64 },
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Camera {
     pub hsize: usize,
     pub vsize: usize,
-    pub field_of_view: f64,
+    pub projection: Projection,
     pub transform: Mat4,
     pub half_height: f64,
     pub half_width: f64,
     pub pixel_size: f64,
+    /// Pixels are split into a grid of `samples` by `samples` cells,
+    /// with one ray traced through each cell and their colors averaged.
+    pub samples: usize,
+    pub sampling: Sampling,
+    /// Seed of the random jitter, each pixel derives its own generator
+    /// from it, so renders are reproducible even across threads.
+    pub seed: u64,
+    /// Radius of the lens, rays start from random points on it,
+    /// blurring whatever lies away from the focal plane.
+    /// With an aperture of 0, the camera is a perfect pinhole.
+    pub aperture: f64,
+    /// Distance from the camera to the plane in perfect focus.
+    pub focal_distance: f64,
 }
 
 impl Camera {
@@ -23,7 +75,10 @@ impl Camera {
     /// let camera = Camera::new(160, 120, PI / 2.0);
     /// assert_eq!(camera.hsize, 160);
     /// assert_eq!(camera.vsize, 120);
-    /// assert_eq!(camera.field_of_view, PI / 2.0);
+    /// assert_eq!(
+    ///     camera.projection,
+    ///     Projection::Perspective { field_of_view: PI / 2.0 }
+    /// );
     /// assert_eq!(camera.transform, Mat4::identity());
     ///
     /// // The pixel size for a horizontal canvas
@@ -35,42 +90,142 @@ impl Camera {
     /// assert!(is_equal(camera.pixel_size, 0.01));
     /// ```
     pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Self {
+        Self::with_projection(hsize, vsize, Projection::Perspective { field_of_view })
+    }
+
+    /// Pixels stay square, so an orthographic view plane whose aspect
+    /// differs from the canvas is widened along one side to fit it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let projection = Projection::Orthographic {
+    ///     width: 4.0,
+    ///     height: 1.0,
+    /// };
+    /// let camera = Camera::with_projection(200, 100, projection);
+    /// assert!(is_equal(camera.pixel_size, 0.02));
+    /// assert!(is_equal(camera.half_width, 2.0));
+    /// assert!(is_equal(camera.half_height, 1.0));
+    /// ```
+    pub fn with_projection(hsize: usize, vsize: usize, projection: Projection) -> Self {
         let transform = Mat4::identity();
-        let half_view = (field_of_view / 2.0).tan();
         let aspect = hsize as f64 / vsize as f64;
         let half_width;
         let half_height;
-        if aspect >= 1.0 {
-            half_width = half_view;
-            half_height = half_view / aspect;
-        } else {
-            half_width = half_view * aspect;
-            half_height = half_view;
+        match projection {
+            Projection::Perspective { field_of_view } => {
+                let half_view = (field_of_view / 2.0).tan();
+                if aspect >= 1.0 {
+                    half_width = half_view;
+                    half_height = half_view / aspect;
+                } else {
+                    half_width = half_view * aspect;
+                    half_height = half_view;
+                }
+            }
+            Projection::Orthographic { width, height } => {
+                if width / height >= aspect {
+                    half_width = width / 2.0;
+                    half_height = half_width / aspect;
+                } else {
+                    half_height = height / 2.0;
+                    half_width = half_height * aspect;
+                }
+            }
         }
         let pixel_size = (half_width * 2.0) / hsize as f64;
         Self {
             hsize,
             vsize,
-            field_of_view,
+            projection,
             transform,
             half_height,
             half_width,
             pixel_size,
+            samples: 1,
+            sampling: Sampling::Regular,
+            seed: 0,
+            aperture: 0.0,
+            focal_distance: 1.0,
         }
     }
 
+    /// Sets how sub-samples are placed within pixels,
+    /// and the seed of the random jitter.
+    pub fn set_sampling(&mut self, sampling: Sampling, seed: u64) {
+        self.sampling = sampling;
+        self.seed = seed;
+    }
+
+    /// With an `aperture` above 0, the ray starts from a random
+    /// point of the lens, picked by a generator seeded for the pixel.
+    /// Orthographic cameras have no lens and ignore the aperture.
+    ///
+    /// `transform` is public, so it is inverted on every call,
+    /// whereas renders invert it once for all of their rays.
     pub fn ray_for_pixel(&self, x: usize, y: usize) -> Ray {
-        let x_offset = (x as f64 + 0.5) * self.pixel_size;
-        let y_offset = (y as f64 + 0.5) * self.pixel_size;
+        self.ray_through_pixel(&self.transform.inverse(), x, y)
+    }
+
+    /// Same as `ray_for_pixel`, given the inverse of `transform`,
+    /// so renders invert it once rather than for every ray.
+    fn ray_through_pixel(&self, inverse: &Mat4, x: usize, y: usize) -> Ray {
+        self.ray_for_position(inverse, x as f64 + 0.5, y as f64 + 0.5, (x, y), &mut None)
+    }
+
+    /// Returns the random generator of a pixel, derived from `seed`.
+    fn pixel_rng(&self, x: usize, y: usize) -> StdRng {
+        // spread pixel indices apart so neighboring seeds don't share generators
+        let index = (y * self.hsize + x) as u64;
+        StdRng::seed_from_u64(
+            self.seed
+                .wrapping_add(index.wrapping_mul(0x9E37_79B9_7F4A_7C15)),
+        )
+    }
+
+    /// Returns the ray through a position on the canvas, in pixels
+    /// from its top left corner, `inverse` being the inverse of `transform`.
+    /// `rng` is the generator of `pixel`, seeded by the lens if still `None`.
+    fn ray_for_position(
+        &self,
+        inverse: &Mat4,
+        x: f64,
+        y: f64,
+        pixel: (usize, usize),
+        rng: &mut Option<StdRng>,
+    ) -> Ray {
+        let x_offset = x * self.pixel_size;
+        let y_offset = y * self.pixel_size;
         let world_x = self.half_width - x_offset;
         let world_y = self.half_height - y_offset;
-        let pixel = self.transform.inverse()
+        if let Projection::Orthographic { .. } = self.projection {
+            let origin = inverse
+                * Point {
+                    x: world_x,
+                    y: world_y,
+                    z: 0.0,
+                };
+            let direction = (inverse
+                * Vector {
+                    x: 0.0,
+                    y: 0.0,
+                    z: -1.0,
+                })
+            .normalize();
+            return Ray { origin, direction };
+        }
+        if self.aperture > 0.0 {
+            return self.ray_through_lens(inverse, world_x, world_y, pixel, rng);
+        }
+        let pixel = inverse
             * Point {
                 x: world_x,
                 y: world_y,
                 z: -1.0,
             };
-        let origin = self.transform.inverse()
+        let origin = inverse
             * Point {
                 x: 0.0,
                 y: 0.0,
@@ -80,6 +235,39 @@ impl Camera {
         Ray { origin, direction }
     }
 
+    /// Returns the ray from a random point of the lens to where
+    /// the pinhole ray through (`world_x`, `world_y`) crosses
+    /// the focal plane.
+    fn ray_through_lens(
+        &self,
+        inverse: &Mat4,
+        world_x: f64,
+        world_y: f64,
+        pixel: (usize, usize),
+        rng: &mut Option<StdRng>,
+    ) -> Ray {
+        // seeded here rather than for every pixel, so
+        // renders without depth of field don't pay for it
+        let rng = rng.get_or_insert_with(|| self.pixel_rng(pixel.0, pixel.1));
+        let focal_point = inverse
+            * Point {
+                x: world_x * self.focal_distance,
+                y: world_y * self.focal_distance,
+                z: -self.focal_distance,
+            };
+        // uniform over the disk, hence the square root
+        let radius = self.aperture * rng.gen::<f64>().sqrt();
+        let angle = rng.gen::<f64>() * std::f64::consts::TAU;
+        let origin = inverse
+            * Point {
+                x: radius * angle.cos(),
+                y: radius * angle.sin(),
+                z: 0.0,
+            };
+        let direction = (focal_point - origin).normalize();
+        Ray { origin, direction }
+    }
+
     /// # Examples
     ///
     /// ```
@@ -95,13 +283,293 @@ impl Camera {
     /// let image = camera.render(&world);
     /// assert_eq!(image[(5, 5)], color![0.38066, 0.47583, 0.2855]);
     /// ```
+    ///
+    /// Pixels whose ray hits nothing are left black
+    /// and transparent, with an alpha of 0. With several samples,
+    /// the alpha is the fraction of samples that hit something.
+    ///
+    /// Rendering is spread across the available threads, or across
+    /// the rayon thread pool with the `rayon` feature enabled,
+    /// the result is identical to `render_single_threaded`.
     pub fn render(&self, world: &World) -> Canvas {
+        self.render_with_progress(world, |_, _| {})
+    }
+
+    /// Same as `render`, calling `on_row` on the calling thread
+    /// with the number of rows done and the total number of rows,
+    /// each time a row is complete. Rows complete in any order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// # use std::f64::consts::PI;
+    /// let world = World::default();
+    /// let camera = Camera::new(11, 11, PI / 2.0);
+    /// let mut calls = 0;
+    /// camera.render_with_progress(&world, |done, total| {
+    ///     calls += 1;
+    ///     println!("{}%", done * 100 / total);
+    /// });
+    /// assert_eq!(calls, 11);
+    /// ```
+    pub fn render_with_progress(&self, world: &World, on_row: impl FnMut(usize, usize)) -> Canvas {
+        self.render_counted(world, on_row).0
+    }
+
+    /// Same as `render`, also returning the work done by the render.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// # use std::f64::consts::PI;
+    /// let world = World::default();
+    /// let camera = Camera::new(11, 11, PI / 2.0);
+    /// let (_, stats) = camera.render_stats(&world);
+    /// assert_eq!(stats.primary_rays, 121);
+    /// ```
+    pub fn render_stats(&self, world: &World) -> (Canvas, RenderStats) {
+        self.render_counted(world, |_, _| {})
+    }
+
+    /// Renders in parallel, counting the work done by every row.
+    fn render_counted(
+        &self,
+        world: &World,
+        mut on_row: impl FnMut(usize, usize),
+    ) -> (Canvas, RenderStats) {
+        let (sender, receiver) = mpsc::channel();
+        let mut image = Canvas::new(self.hsize, self.vsize);
+        let mut total = RenderStats::default();
+        // inverted once, then shared by every worker
+        let inverse = self.transform.inverse();
+        let inverse = &inverse;
+        thread::scope(|scope| {
+            scope.spawn(move || self.render_parallel(world, inverse, sender));
+            for (done, (row, stats)) in receiver.iter().enumerate() {
+                for (x, y, (color, alpha)) in row {
+                    image[(x, y)] = color;
+                    image.set_alpha((x, y), alpha);
+                }
+                total += stats;
+                on_row(done + 1, self.vsize);
+            }
+        });
+        (image, total)
+    }
+
+    /// Splits rows evenly across the available threads,
+    /// sending each row to `sender` once rendered.
+    #[cfg(not(feature = "rayon"))]
+    fn render_parallel(&self, world: &World, inverse: &Mat4, sender: Sender<RenderedRow>) {
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+        let rows_per_thread = self.vsize.div_ceil(threads).max(1);
+        thread::scope(|scope| {
+            for start in (0..self.vsize).step_by(rows_per_thread) {
+                let end = (start + rows_per_thread).min(self.vsize);
+                let sender = sender.clone();
+                scope.spawn(move || {
+                    for y in start..end {
+                        sender.send(self.render_row(world, inverse, y)).unwrap();
+                    }
+                });
+            }
+        });
+    }
+
+    /// Traces rows in parallel with rayon,
+    /// sending each row to `sender` once rendered.
+    #[cfg(feature = "rayon")]
+    fn render_parallel(&self, world: &World, inverse: &Mat4, sender: Sender<RenderedRow>) {
+        use rayon::prelude::*;
+
+        (0..self.vsize)
+            .into_par_iter()
+            .for_each_with(sender, |sender, y| {
+                sender.send(self.render_row(world, inverse, y)).unwrap();
+            });
+    }
+
+    /// Same as `render`, on the calling thread only.
+    pub fn render_single_threaded(&self, world: &World) -> Canvas {
+        self.render_region(world, 0, 0, self.hsize, self.vsize)
+    }
+
+    /// Same as `render_single_threaded`, tracing only the pixels
+    /// from (`x0`, `y0`) included to (`x1`, `y1`) excluded,
+    /// clamped to the canvas. The other pixels are left black.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// # use std::f64::consts::PI;
+    /// let world = World::default();
+    /// let mut camera = Camera::new(11, 11, PI / 2.0);
+    /// camera.transform = Mat4::identity().view_transform(
+    ///     point![0, 0, -5],
+    ///     point![0, 0, 0],
+    ///     vector![0, 1, 0],
+    /// );
+    /// let image = camera.render_region(&world, 4, 4, 7, 7);
+    /// assert_eq!(image[(5, 5)], color![0.38066, 0.47583, 0.2855]);
+    /// assert_eq!(image[(8, 5)], Color::BLACK);
+    /// ```
+    pub fn render_region(
+        &self,
+        world: &World,
+        x0: usize,
+        y0: usize,
+        x1: usize,
+        y1: usize,
+    ) -> Canvas {
+        let columns = x0.min(self.hsize)..x1.min(self.hsize);
+        let rows = y0.min(self.vsize)..y1.min(self.vsize);
+        let mut image = Canvas::new(self.hsize, self.vsize);
+        let inverse = self.transform.inverse();
+        for (x, y, (color, alpha)) in self.render_rect(world, &inverse, columns, rows) {
+            image[(x, y)] = color;
+            image.set_alpha((x, y), alpha);
+        }
+        image
+    }
+
+    /// Renders row `y`, along with the work it took.
+    fn render_row(&self, world: &World, inverse: &Mat4, y: usize) -> RenderedRow {
+        stats::measure(|| self.render_rect(world, inverse, 0..self.hsize, y..y + 1))
+    }
+
+    /// Renders every pixel within both `columns` and `rows`,
+    /// `inverse` being the inverse of `transform`.
+    fn render_rect(
+        &self,
+        world: &World,
+        inverse: &Mat4,
+        columns: Range<usize>,
+        rows: Range<usize>,
+    ) -> Vec<RenderedPixel> {
+        let mut pixels = Vec::with_capacity(rows.len() * columns.len());
+        // one buffer for the intersections of every ray of the rectangle
+        let mut buffer = Vec::new();
+        for y in rows {
+            for x in columns.clone() {
+                let pixel = self.render_pixel(world, inverse, x, y, &mut buffer);
+                pixels.push((x, y, pixel));
+            }
+        }
+        pixels
+    }
+
+    /// Returns the average color of the samples of a pixel,
+    /// along with the fraction of them that hit something.
+    fn render_pixel<'w>(
+        &self,
+        world: &'w World,
+        inverse: &Mat4,
+        x: usize,
+        y: usize,
+        buffer: &mut Vec<Intersection<'w>>,
+    ) -> (Color, f64) {
+        if self.samples <= 1 && self.sampling == Sampling::Regular {
+            stats::count(|stats| stats.primary_rays += 1);
+            return match world.trace_into(self.ray_through_pixel(inverse, x, y), buffer) {
+                Some(color) => (color, 1.0),
+                None => (world.background, 0.0),
+            };
+        }
+        let samples = self.samples.max(1);
+        // seeded on first use, by the jitter or the lens
+        let mut rng = None;
+        let mut color = Color::BLACK;
+        let mut hits = 0;
+        for j in 0..samples {
+            for i in 0..samples {
+                let (dx, dy) = match self.sampling {
+                    Sampling::Regular => (0.5, 0.5),
+                    Sampling::Jittered => {
+                        let rng = rng.get_or_insert_with(|| self.pixel_rng(x, y));
+                        (rng.gen::<f64>(), rng.gen::<f64>())
+                    }
+                };
+                let ray = self.ray_for_position(
+                    inverse,
+                    x as f64 + (i as f64 + dx) / samples as f64,
+                    y as f64 + (j as f64 + dy) / samples as f64,
+                    (x, y),
+                    &mut rng,
+                );
+                stats::count(|stats| stats.primary_rays += 1);
+                match world.trace_into(ray, buffer) {
+                    Some(sample) => {
+                        color += sample;
+                        hits += 1;
+                    }
+                    None => color += world.background,
+                }
+            }
+        }
+        let count = (samples * samples) as f64;
+        (color / count, hits as f64 / count)
+    }
+
+    /// Renders the surface normal at each hit, each axis from -1 to 1
+    /// mapped to a channel from 0 to 1, regardless of the materials
+    /// and lights. Normals are shown as the shape computes them,
+    /// before being flipped toward the eye from inside a shape.
+    /// Useful to check the normals of a shape that renders wrong.
+    pub fn render_normals(&self, world: &World) -> Canvas {
+        let inverse = self.transform.inverse();
+        let mut image = Canvas::new(self.hsize, self.vsize);
+        for y in 0..(self.vsize) {
+            for x in 0..(self.hsize) {
+                let ray = self.ray_through_pixel(&inverse, x, y);
+                let intersections = world.intersect(ray);
+                if let Some(hit) = Intersection::hit(&intersections) {
+                    let comps = hit.prepare(ray);
+                    let normal = if comps.inside {
+                        -comps.normal
+                    } else {
+                        comps.normal
+                    };
+                    image[(x, y)] = Color {
+                        red: (normal.x + 1.0) / 2.0,
+                        green: (normal.y + 1.0) / 2.0,
+                        blue: (normal.z + 1.0) / 2.0,
+                    };
+                }
+            }
+        }
+        image
+    }
+
+    /// Renders the Lambert term, `light_vector.dot(normal)` clamped
+    /// to zero and summed over the lights, as grayscale,
+    /// regardless of the materials.
+    /// Useful to diagnose the orientation of surfaces toward the light.
+    pub fn render_ndotl(&self, world: &World) -> Canvas {
+        let inverse = self.transform.inverse();
         let mut image = Canvas::new(self.hsize, self.vsize);
         for y in 0..(self.vsize) {
             for x in 0..(self.hsize) {
-                let ray = self.ray_for_pixel(x, y);
-                let color = world.color_at(ray);
-                image[(x, y)] = color;
+                let ray = self.ray_through_pixel(&inverse, x, y);
+                let intersections = world.intersect(ray);
+                if let Some(hit) = Intersection::hit(&intersections) {
+                    let comps = hit.prepare(ray);
+                    let mut ndotl = 0.0;
+                    for light in &world.lights {
+                        let directions = light.directions(comps.over_point);
+                        for (lightv, _) in &directions {
+                            ndotl += lightv.dot(comps.normal).max(0.0) / directions.len() as f64;
+                        }
+                    }
+                    let ndotl = ndotl.min(1.0);
+                    image[(x, y)] = Color {
+                        red: ndotl,
+                        green: ndotl,
+                        blue: ndotl,
+                    };
+                }
             }
         }
         image
@@ -111,8 +579,11 @@ impl Camera {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::light::Light;
     use crate::point;
-    use crate::{vector, vector::Vector};
+    use crate::prelude::is_equal;
+    use crate::sphere::Sphere;
+    use crate::{color, vector};
     use std::f64::consts::PI;
     use std::f64::consts::SQRT_2;
 
@@ -137,4 +608,282 @@ mod tests {
         assert_eq!(ray.origin, point![0, 2, -5]);
         assert_eq!(ray.direction, vector![SQRT_2 / 2.0, 0, -SQRT_2 / 2.0]);
     }
+
+    #[test]
+    fn test_orthographic() {
+        // Orthographic rays are parallel, starting across the view plane
+        let projection = Projection::Orthographic {
+            width: 4.0,
+            height: 2.0,
+        };
+        let mut camera = Camera::with_projection(200, 100, projection);
+        let ray = camera.ray_for_pixel(0, 0);
+        assert_eq!(ray.origin, point![1.99, 0.99, 0]);
+        assert_eq!(ray.direction, vector![0, 0, -1]);
+        camera.transform = Mat4::identity().translate(0, -2, 5).rotate_y(PI / 4.0);
+        let ray = camera.ray_for_pixel(100, 50);
+        assert_eq!(ray.direction, vector![SQRT_2 / 2.0, 0, -SQRT_2 / 2.0]);
+
+        // A sphere covers as many pixels however far it is
+        let mut world = World::new(Light::point(point![0, 0, -100], color![1, 1, 1]));
+        world.push(Sphere::new());
+        let coverage = |projection: Projection, distance: f64| {
+            let mut camera = Camera::with_projection(21, 21, projection);
+            camera.transform = Mat4::identity().view_transform(
+                point![0, 0, -distance],
+                point![0, 0, 0],
+                vector![0, 1, 0],
+            );
+            let image = camera.render(&world);
+            (0..21)
+                .flat_map(|y| (0..21).map(move |x| (x, y)))
+                .filter(|&index| image.alpha(index) > 0.0)
+                .count()
+        };
+        let orthographic = Projection::Orthographic {
+            width: 3.0,
+            height: 3.0,
+        };
+        assert!(coverage(orthographic, 5.0) > 0);
+        assert_eq!(coverage(orthographic, 5.0), coverage(orthographic, 50.0));
+        let perspective = Projection::Perspective {
+            field_of_view: PI / 4.0,
+        };
+        assert!(coverage(perspective, 5.0) > coverage(perspective, 50.0));
+    }
+
+    #[test]
+    fn test_render_ndotl() {
+        let mut camera = Camera::new(11, 11, PI / 2.0);
+        camera.transform =
+            Mat4::identity().view_transform(point![0, 0, -5], point![0, 0, 0], vector![0, 1, 0]);
+
+        // A surface directly facing the light renders white
+        let mut world = World::new(Light::point(point![0, 0, -10], color![1, 1, 1]));
+        world.push(Sphere::new());
+        let image = camera.render_ndotl(&world);
+        assert_eq!(image[(5, 5)], color![1, 1, 1]);
+
+        // A surface perpendicular to the light renders black
+        world.lights[0] = Light::point(point![10, 0, -1], color![1, 1, 1]);
+        let image = camera.render_ndotl(&world);
+        assert_eq!(image[(5, 5)], color![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_render_normals() {
+        let mut camera = Camera::new(11, 11, PI / 2.0);
+        camera.transform =
+            Mat4::identity().view_transform(point![0, 0, -5], point![0, 0, 0], vector![0, 1, 0]);
+        let mut world = World::new(Light::point(point![0, 0, -10], color![1, 1, 1]));
+        world.lights.clear();
+        world.push(Sphere::new());
+        let image = camera.render_normals(&world);
+
+        // The front of a sphere faces the camera
+        assert_eq!(image[(5, 5)], color![0.5, 0.5, 0]);
+
+        // Every hit encodes a unit normal, rays that miss stay black
+        for y in 0..camera.vsize {
+            for x in 0..camera.hsize {
+                let color = image[(x, y)];
+                if color == Color::BLACK {
+                    continue;
+                }
+                let normal = vector![
+                    color.red * 2.0 - 1.0,
+                    color.green * 2.0 - 1.0,
+                    color.blue * 2.0 - 1.0
+                ];
+                assert!(is_equal(normal.magnitude(), 1.0));
+            }
+        }
+        assert_eq!(image[(0, 0)], Color::BLACK);
+
+        // Normals seen from inside a shape aren't flipped
+        camera.transform = Mat4::identity();
+        let image = camera.render_normals(&world);
+        assert_eq!(image[(5, 5)], color![0.5, 0.5, 0]);
+    }
+
+    #[test]
+    fn test_render() {
+        // Rendering in parallel, with or without the `rayon` feature,
+        // matches rendering on a single thread
+        let world = World::default();
+        let mut camera = Camera::new(23, 17, PI / 2.0);
+        camera.transform =
+            Mat4::identity().view_transform(point![0, 0, -5], point![0, 0, 0], vector![0, 1, 0]);
+        let parallel = camera.render(&world);
+        let single = camera.render_single_threaded(&world);
+        for y in 0..camera.vsize {
+            for x in 0..camera.hsize {
+                assert_eq!(parallel[(x, y)], single[(x, y)]);
+                assert_eq!(parallel.alpha((x, y)), single.alpha((x, y)));
+            }
+        }
+        assert_eq!(parallel.to_ppm(), single.to_ppm());
+
+        // Rendering inverts the transform once, giving the same rays
+        // as `ray_for_pixel`
+        camera.transform = camera.transform.rotate_axis(vector![1, 2, 3], 0.1);
+        let image = camera.render(&world);
+        for y in 0..camera.vsize {
+            for x in 0..camera.hsize {
+                let color = world.color_at(camera.ray_for_pixel(x, y));
+                let pixel = image[(x, y)];
+                assert_eq!(pixel.red.to_bits(), color.red.to_bits());
+                assert_eq!(pixel.green.to_bits(), color.green.to_bits());
+                assert_eq!(pixel.blue.to_bits(), color.blue.to_bits());
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_region() {
+        let world = World::default();
+        let mut camera = Camera::new(23, 17, PI / 2.0);
+        camera.transform =
+            Mat4::identity().view_transform(point![0, 0, -5], point![0, 0, 0], vector![0, 1, 0]);
+        let full = camera.render(&world);
+
+        // A region covering the whole canvas matches a full render
+        let region = camera.render_region(&world, 0, 0, camera.hsize, camera.vsize);
+        assert_eq!(region.to_ppm(), full.to_ppm());
+
+        // A region past the edges is clamped, pixels outside of it stay black
+        let region = camera.render_region(&world, 10, 6, 100, 100);
+        for y in 0..camera.vsize {
+            for x in 0..camera.hsize {
+                if x >= 10 && y >= 6 {
+                    assert_eq!(region[(x, y)], full[(x, y)]);
+                    assert_eq!(region.alpha((x, y)), full.alpha((x, y)));
+                } else {
+                    assert_eq!(region[(x, y)], Color::BLACK);
+                }
+            }
+        }
+
+        // An empty or reversed region renders nothing
+        let region = camera.render_region(&world, 30, 20, 40, 40);
+        assert_eq!(region.to_ppm(), Canvas::new(23, 17).to_ppm());
+        let region = camera.render_region(&world, 15, 10, 5, 2);
+        assert_eq!(region.to_ppm(), Canvas::new(23, 17).to_ppm());
+    }
+
+    #[test]
+    fn test_render_with_progress() {
+        // Progress is reported once per row, up to the total
+        let world = World::default();
+        let mut camera = Camera::new(23, 17, PI / 2.0);
+        camera.transform =
+            Mat4::identity().view_transform(point![0, 0, -5], point![0, 0, 0], vector![0, 1, 0]);
+        let mut progress = Vec::new();
+        let image = camera.render_with_progress(&world, |done, total| progress.push((done, total)));
+        let expected: Vec<_> = (1..=camera.vsize)
+            .map(|done| (done, camera.vsize))
+            .collect();
+        assert_eq!(progress, expected);
+
+        // and does not change the image
+        assert_eq!(
+            image.to_ppm(),
+            camera.render_single_threaded(&world).to_ppm()
+        );
+    }
+
+    #[test]
+    fn test_render_stats() {
+        let world = World::default();
+        let mut camera = Camera::new(23, 17, PI / 2.0);
+        camera.transform =
+            Mat4::identity().view_transform(point![0, 0, -5], point![0, 0, 0], vector![0, 1, 0]);
+        let (image, stats) = camera.render_stats(&world);
+        assert_eq!(image.to_ppm(), camera.render(&world).to_ppm());
+
+        // One primary ray per sample, each tested against both spheres
+        let hits = (0..camera.vsize)
+            .flat_map(|y| (0..camera.hsize).map(move |x| (x, y)))
+            .filter(|&index| image.alpha(index) > 0.0)
+            .count();
+        assert_eq!(stats.primary_rays, 23 * 17);
+        assert_eq!(stats.shadow_rays, hits);
+        assert_eq!(stats.intersection_tests, 2 * (stats.primary_rays + hits));
+
+        // Counts start over with each render
+        camera.samples = 2;
+        let (_, stats) = camera.render_stats(&world);
+        assert_eq!(stats.primary_rays, 4 * 23 * 17);
+    }
+
+    #[test]
+    fn test_sampling() {
+        let world = World::default();
+        let mut camera = Camera::new(11, 11, PI / 2.0);
+        camera.transform =
+            Mat4::identity().view_transform(point![0, 0, -5], point![0, 0, 0], vector![0, 1, 0]);
+
+        // A single regular sample is the center of the pixel
+        let image = camera.render(&world);
+        assert_eq!(image[(5, 5)], color![0.38066, 0.47583, 0.2855]);
+
+        // Regular sub-samples average the colors of a grid of rays
+        camera.samples = 3;
+        let image = camera.render(&world);
+        assert_eq!(image.alpha((5, 5)), 1.0);
+        assert_eq!(image.alpha((0, 0)), 0.0);
+
+        // Jittered sampling with the same seed is reproducible
+        camera.set_sampling(Sampling::Jittered, 0);
+        let first = camera.render(&world);
+        let second = camera.render_single_threaded(&world);
+        assert_eq!(first.to_ppm(), second.to_ppm());
+        for y in 0..camera.vsize {
+            for x in 0..camera.hsize {
+                assert_eq!(first[(x, y)], second[(x, y)]);
+            }
+        }
+
+        // A different seed jitters the samples differently
+        camera.set_sampling(Sampling::Jittered, 1);
+        let third = camera.render(&world);
+        assert!((0..camera.vsize)
+            .flat_map(|y| (0..camera.hsize).map(move |x| (x, y)))
+            .any(|index| first[index] != third[index]));
+    }
+
+    #[test]
+    fn test_aperture() {
+        // Near the focal plane stays sharp while farther away blurs
+        let mut world = World::new(Light::point(point![0, 10, 10], color![1, 1, 1]));
+        world.push(Sphere::new().set_transform(Mat4::identity().translate(-1.5, 0, -5)));
+        world.push(
+            Sphere::new().set_transform(Mat4::identity().uniform_scale(10).translate(15, 0, -50)),
+        );
+        let mut camera = Camera::new(40, 20, PI / 2.0);
+        camera.samples = 3;
+        let sharp = camera.render(&world);
+        camera.aperture = 0.25;
+        camera.focal_distance = 4.0;
+        let blurred = camera.render(&world);
+        let difference = |columns: Range<usize>| {
+            let mut total = 0.0;
+            for y in 0..camera.vsize {
+                for x in columns.clone() {
+                    let delta = sharp[(x, y)] - blurred[(x, y)];
+                    total += delta.red.abs() + delta.green.abs() + delta.blue.abs();
+                }
+            }
+            total
+        };
+        // the camera faces -z, so +x is on the left of the canvas
+        let far = difference(0..20);
+        let near = difference(20..40);
+        assert!(near * 4.0 < far, "near: {}, far: {}", near, far);
+
+        // A camera with no aperture is a pinhole
+        camera.aperture = 0.0;
+        let ray = camera.ray_for_pixel(20, 10);
+        assert_eq!(ray.origin, point![0, 0, 0]);
+    }
 }
@@ -1,34 +1,229 @@
 use crate::prelude::*;
+use std::fmt;
 use std::fs::File;
 use std::io::Write;
 use std::ops::{Index, IndexMut};
 
+/// Error returned by `Canvas::from_ppm`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PpmError {
+    /// The file doesn't start with the `P3` magic number.
+    MagicNumber(String),
+    /// A header value or a sample is not a valid number.
+    InvalidNumber(String),
+    /// The file ends before every sample is read.
+    UnexpectedEnd,
+}
+
+impl fmt::Display for PpmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MagicNumber(magic) => write!(f, "unsupported PPM magic number {:?}", magic),
+            Self::InvalidNumber(word) => write!(f, "invalid PPM number {:?}", word),
+            Self::UnexpectedEnd => write!(f, "unexpected end of PPM data"),
+        }
+    }
+}
+
+impl std::error::Error for PpmError {}
+
+#[derive(Clone)]
 pub struct Canvas {
     pub width: usize,
     pub height: usize,
+    /// When set, pixels are sanitized with `Color::sanitize`
+    /// before being written, so NaN and infinite channels
+    /// never reach the output.
+    pub sanitize: bool,
     array: Vec<Color>,
+    /// Coverage of each pixel, from 0 (transparent) to 1 (opaque).
+    /// Ignored when writing PPM files, used by `over`.
+    alpha: Vec<f64>,
 }
 
 impl Canvas {
     /// Creates a new `Canvas`, every pixel is
-    /// initialized to black, `color![0, 0, 0]`.
+    /// initialized to opaque black, `color![0, 0, 0]`.
     pub fn new(width: usize, height: usize) -> Self {
+        Self::new_filled(width, height, Color::BLACK)
+    }
+
+    /// Creates a new `Canvas`, every pixel is
+    /// initialized to opaque `color`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let canvas = Canvas::new_filled(10, 20, color![0.2, 0.4, 1]);
+    /// assert_eq!(canvas[(9, 19)], color![0.2, 0.4, 1]);
+    /// assert_eq!(canvas.alpha((0, 0)), 1.0);
+    /// ```
+    pub fn new_filled(width: usize, height: usize, color: Color) -> Self {
         Self {
             width,
             height,
-            array: vec![
-                Color {
-                    red: 0.0,
-                    green: 0.0,
-                    blue: 0.0
-                };
-                width * height
-            ],
+            sanitize: false,
+            array: vec![color; width * height],
+            alpha: vec![1.0; width * height],
+        }
+    }
+
+    pub fn alpha(&self, index: (usize, usize)) -> f64 {
+        self.alpha[self.offset(index)]
+    }
+
+    pub fn set_alpha(&mut self, index: (usize, usize), alpha: f64) {
+        let offset = self.offset(index);
+        self.alpha[offset] = alpha;
+    }
+
+    /// Sets every pixel to `color`, leaving their alpha as is.
+    pub fn fill(&mut self, color: Color) {
+        self.array.fill(color);
+    }
+
+    /// Copies `source`, color and alpha, with its top left corner at
+    /// (`x`, `y`). Only the part of `source` overlapping the canvas is
+    /// copied, the rest, even all of it, is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let mut sheet = Canvas::new(3, 2);
+    /// let mut tile = Canvas::new(2, 2);
+    /// tile.fill(Color::WHITE);
+    /// sheet.blit(&tile, 2, 1);
+    /// assert_eq!(sheet[(2, 1)], Color::WHITE);
+    /// assert_eq!(sheet[(1, 1)], Color::BLACK);
+    /// ```
+    pub fn blit(&mut self, source: &Canvas, x: usize, y: usize) {
+        let width = source.width.min(self.width.saturating_sub(x));
+        let height = source.height.min(self.height.saturating_sub(y));
+        for row in 0..height {
+            let from = row * source.width;
+            let to = (y + row) * self.width + x;
+            self.array[to..to + width].copy_from_slice(&source.array[from..from + width]);
+            self.alpha[to..to + width].copy_from_slice(&source.alpha[from..from + width]);
         }
     }
 
+    /// Returns the canvas with the Reinhard operator, `c / (1 + c)`,
+    /// applied to each channel, bringing any brightness back below 1.
+    /// Negative channels become 0, and infinite ones 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let mut canvas = Canvas::new(1, 1);
+    /// canvas[(0, 0)] = color![3, 1, 0];
+    /// assert_eq!(canvas.tone_map_reinhard()[(0, 0)], color![0.75, 0.5, 0]);
+    /// ```
+    pub fn tone_map_reinhard(&self) -> Canvas {
+        let reinhard = |channel: f64| {
+            if channel == f64::INFINITY {
+                return 1.0;
+            }
+            let channel = channel.max(0.0);
+            channel / (1.0 + channel)
+        };
+        self.map(|pixel| Color {
+            red: reinhard(pixel.red),
+            green: reinhard(pixel.green),
+            blue: reinhard(pixel.blue),
+        })
+    }
+
+    /// Returns the canvas with every pixel multiplied by `2^stops`,
+    /// brightening it for positive `stops`, darkening it for negative ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let mut canvas = Canvas::new(1, 1);
+    /// canvas[(0, 0)] = color![0.2, 0.4, 1];
+    /// assert_eq!(canvas.with_exposure(1.0)[(0, 0)], color![0.4, 0.8, 2]);
+    /// assert_eq!(canvas.with_exposure(-2.0)[(0, 0)], color![0.05, 0.1, 0.25]);
+    /// ```
+    pub fn with_exposure(&self, stops: f64) -> Canvas {
+        let factor = stops.exp2();
+        self.map(|pixel| pixel * factor)
+    }
+
+    /// Returns a canvas of the same size, alpha and settings,
+    /// with `f` applied to every pixel.
+    fn map<F>(&self, f: F) -> Canvas
+    where
+        F: Fn(Color) -> Color,
+    {
+        Canvas {
+            width: self.width,
+            height: self.height,
+            sanitize: self.sanitize,
+            array: self.array.iter().map(|&pixel| f(pixel)).collect(),
+            alpha: self.alpha.clone(),
+        }
+    }
+
+    /// Composites `self` over `background`, both canvases
+    /// having the same size, using the alpha of each pixel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let mut foreground = Canvas::new(1, 1);
+    /// foreground[(0, 0)] = color![1, 0, 0];
+    /// foreground.set_alpha((0, 0), 0.5);
+    /// let background = Canvas::new(1, 1);
+    /// let image = foreground.over(&background);
+    /// assert_eq!(image[(0, 0)], color![0.5, 0, 0]);
+    /// assert_eq!(image.alpha((0, 0)), 1.0);
+    /// ```
+    pub fn over(&self, background: &Canvas) -> Canvas {
+        if self.width != background.width || self.height != background.height {
+            panic!(
+                "canvas size mismatch: {} by {} over {} by {}",
+                self.width, self.height, background.width, background.height
+            );
+        }
+        let mut image = Canvas::new(self.width, self.height);
+        image.sanitize = self.sanitize;
+        for offset in 0..self.array.len() {
+            let front_alpha = self.alpha[offset];
+            let back_alpha = background.alpha[offset] * (1.0 - front_alpha);
+            let alpha = front_alpha + back_alpha;
+            image.alpha[offset] = alpha;
+            image.array[offset] = if alpha > 0.0 {
+                (self.array[offset] * front_alpha + background.array[offset] * back_alpha) / alpha
+            } else {
+                Color::BLACK
+            };
+        }
+        image
+    }
+
     /// Returns a PPM-formatted string.
     pub fn to_ppm(&self) -> String {
+        self.to_ppm_gamma(1.0)
+    }
+
+    /// Returns a PPM-formatted string, gamma encoding each
+    /// channel, once clamped to 0 through 1, as `channel.powf(1.0 / gamma)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let mut canvas = Canvas::new(2, 1);
+    /// canvas[(0, 0)] = color![0.5, 0.218, 1];
+    /// canvas[(1, 0)] = color![-0.5, 0, 1.5];
+    /// assert_eq!(canvas.to_ppm_gamma(2.2), "P3\n2 1\n255\n186 128 255 0 0 255\n");
+    /// ```
+    pub fn to_ppm_gamma(&self, gamma: f64) -> String {
         let mut ppm = String::new();
         ppm.push_str("P3\n");
         ppm.push_str(format!("{} {}\n", self.width, self.height).as_str());
@@ -36,24 +231,144 @@ impl Canvas {
         for height in 0..self.height {
             let mut char_count = 0;
             for width in 0..self.width {
-                let pixel = self[(width, height)];
-                let red = (pixel.red * 255.0).ceil().clamp(0.0, 255.0).to_string();
-                let green = (pixel.green * 255.0).ceil().clamp(0.0, 255.0).to_string();
-                let blue = (pixel.blue * 255.0).ceil().clamp(0.0, 255.0).to_string();
-                char_count = push_color(&mut ppm, &red, char_count);
-                char_count = push_color(&mut ppm, &green, char_count);
-                char_count = push_color(&mut ppm, &blue, char_count);
+                for channel in self.pixel_bytes((width, height), gamma) {
+                    char_count = push_color(&mut ppm, &channel.to_string(), char_count);
+                }
             }
             ppm.push('\n');
         }
         ppm
     }
 
+    /// Parses an ASCII PPM (`P3`) image, scaling samples
+    /// from 0 through the maximum value to 0.0 through 1.0.
+    ///
+    /// Samples may be split across lines anywhere, and
+    /// anything from a `#` to the end of its line is skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let canvas = Canvas::from_ppm("P3\n# a comment\n2 1\n100\n100 50 0\n0 0\n25\n").unwrap();
+    /// assert_eq!(canvas[(0, 0)], color![1, 0.5, 0]);
+    /// assert_eq!(canvas[(1, 0)], color![0, 0, 0.25]);
+    /// ```
+    pub fn from_ppm(source: &str) -> Result<Canvas, PpmError> {
+        let mut words = source
+            .lines()
+            .flat_map(|line| line.split('#').next().unwrap_or("").split_whitespace());
+        let magic = words.next().ok_or(PpmError::UnexpectedEnd)?;
+        if magic != "P3" {
+            return Err(PpmError::MagicNumber(magic.to_string()));
+        }
+        let mut number = || -> Result<usize, PpmError> {
+            let word = words.next().ok_or(PpmError::UnexpectedEnd)?;
+            word.parse()
+                .map_err(|_| PpmError::InvalidNumber(word.to_string()))
+        };
+        let width = number()?;
+        let height = number()?;
+        let maxval = number()?;
+        if maxval == 0 {
+            return Err(PpmError::InvalidNumber(maxval.to_string()));
+        }
+        let mut canvas = Canvas::new(width, height);
+        let scale = 1.0 / maxval as f64;
+        for pixel in canvas.array.iter_mut() {
+            pixel.red = number()? as f64 * scale;
+            pixel.green = number()? as f64 * scale;
+            pixel.blue = number()? as f64 * scale;
+        }
+        Ok(canvas)
+    }
+
+    /// Returns a binary PPM (`P6`) image, each pixel
+    /// written as three bytes, converted as in `to_ppm`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let mut canvas = Canvas::new(2, 1);
+    /// canvas[(1, 0)] = color![1.5, 0.5, -0.5];
+    /// let ppm = canvas.to_ppm_binary();
+    /// assert_eq!(&ppm[..11], b"P6\n2 1\n255\n");
+    /// assert_eq!(&ppm[11..], &[0, 0, 0, 255, 128, 0]);
+    /// ```
+    pub fn to_ppm_binary(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        ppm.reserve(self.width * self.height * 3);
+        for height in 0..self.height {
+            for width in 0..self.width {
+                ppm.extend(self.pixel_bytes((width, height), 1.0));
+            }
+        }
+        ppm
+    }
+
     /// Writes PPM-formatted string of canvas into `path`
     pub fn write(&self, path: &str) -> Result<(), std::io::Error> {
-        File::create(path)?.write(self.to_ppm().as_bytes())?;
+        self.write_to(&mut File::create(path)?)
+    }
+
+    /// Writes PPM-formatted string of canvas into `writer`,
+    /// such as a buffer or `std::io::stdout()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let canvas = Canvas::new(1, 1);
+    /// let mut buffer = Vec::new();
+    /// canvas.write_to(&mut buffer).unwrap();
+    /// assert_eq!(buffer, b"P3\n1 1\n255\n0 0 0\n");
+    /// ```
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
+        writer.write_all(self.to_ppm().as_bytes())
+    }
+
+    /// Writes binary PPM image of canvas into `path`
+    pub fn write_binary(&self, path: &str) -> Result<(), std::io::Error> {
+        File::create(path)?.write_all(&self.to_ppm_binary())?;
         Ok(())
     }
+
+    /// Writes canvas into `path` as an 8-bit RGB PNG image,
+    /// converting colors as in `to_ppm`.
+    #[cfg(feature = "png")]
+    pub fn write_png(&self, path: &str) -> Result<(), std::io::Error> {
+        let mut data = Vec::with_capacity(self.width * self.height * 3);
+        for height in 0..self.height {
+            for width in 0..self.width {
+                data.extend(self.pixel_bytes((width, height), 1.0));
+            }
+        }
+        let file = std::io::BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&data)?;
+        Ok(())
+    }
+
+    /// Returns the red, green and blue channels of a pixel,
+    /// gamma encoded, scaled and rounded to 0 through 255.
+    fn pixel_bytes(&self, index: (usize, usize), gamma: f64) -> [u8; 3] {
+        let mut pixel = self[index];
+        if self.sanitize {
+            pixel = pixel.sanitize();
+        }
+        let pixel = pixel.clamp();
+        let encode = |channel: f64| channel.powf(1.0 / gamma);
+        let (red, green, blue) = Color {
+            red: encode(pixel.red),
+            green: encode(pixel.green),
+            blue: encode(pixel.blue),
+        }
+        .to_rgb8();
+        [red, green, blue]
+    }
 }
 
 fn push_color(ppm: &mut String, color: &str, mut count: usize) -> usize {
@@ -73,12 +388,10 @@ fn push_color(ppm: &mut String, color: &str, mut count: usize) -> usize {
     count
 }
 
-impl Index<(usize, usize)> for Canvas {
-    type Output = Color;
-
-    fn index(&self, index: (usize, usize)) -> &Self::Output {
+impl Canvas {
+    fn offset(&self, index: (usize, usize)) -> usize {
         if index.0 < self.width && index.1 < self.height {
-            return &self.array[index.1 * self.width + index.0];
+            return index.1 * self.width + index.0;
         }
         panic!(
             "index out of bounds: canvas size is {} by {}, index is [({}, {})]",
@@ -87,15 +400,18 @@ impl Index<(usize, usize)> for Canvas {
     }
 }
 
+impl Index<(usize, usize)> for Canvas {
+    type Output = Color;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.array[self.offset(index)]
+    }
+}
+
 impl IndexMut<(usize, usize)> for Canvas {
     fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
-        if index.0 < self.width && index.1 < self.height {
-            return &mut self.array[index.1 * self.width + index.0];
-        }
-        panic!(
-            "index out of bounds: canvas size is {} by {}, index is [({}, {})]",
-            self.width, self.height, index.0, index.1
-        );
+        let offset = self.offset(index);
+        &mut self.array[offset]
     }
 }
 
@@ -104,6 +420,20 @@ mod tests {
     use super::*;
     use crate::color;
 
+    #[test]
+    fn test_new_filled() {
+        let color = color![0.1, 0.5, 0.9];
+        let filled = Canvas::new_filled(7, 3, color);
+        let mut canvas = Canvas::new(7, 3);
+        canvas.fill(color);
+        assert_eq!((filled.width, filled.height), (7, 3));
+        assert_eq!(filled.array, canvas.array);
+        assert_eq!(filled.alpha, canvas.alpha);
+
+        // Filled canvases write out like any other
+        assert_eq!(filled.to_ppm(), canvas.to_ppm());
+    }
+
     #[test]
     fn test_to_ppm() {
         let mut canvas = Canvas::new(5, 3);
@@ -139,5 +469,191 @@ mod tests {
             "153 255 204 153 255 204 153 255 204 153 255 204 153"
         );
         assert_eq!(canvas.to_ppm(), ppm);
+
+        // Sanitized canvas writes NaN as 0 and infinity as 255
+        let mut canvas = Canvas::new(1, 1);
+        canvas.sanitize = true;
+        canvas[(0, 0)] = color![f64::NAN, f64::INFINITY, 0];
+        assert_eq!(canvas.to_ppm(), "P3\n1 1\n255\n0 255 0\n");
+
+        // Channels are rounded to the nearest value, not up
+        let mut canvas = Canvas::new(2, 1);
+        canvas[(0, 0)] = color![0.001, 0.5, 0.999];
+        canvas[(1, 0)] = color![0.2, 0.4, 0.6];
+        assert_eq!(canvas.to_ppm(), "P3\n2 1\n255\n0 128 255 51 102 153\n");
+    }
+
+    #[test]
+    fn test_from_ppm() {
+        // Reading a file with the wrong magic number
+        assert_eq!(
+            Canvas::from_ppm("P32\n1 1\n255\n0 0 0\n").err(),
+            Some(PpmError::MagicNumber("P32".to_string()))
+        );
+
+        // Reading the pixel data, wrapped across lines and around comments
+        let canvas = Canvas::from_ppm(
+            "P3\n# this is a comment\n4 3 # and so is this\n255\n\
+             255 127 0  0 127 255  127 255 0  255 255 255\n\
+             0 0 0  255 0 0  0 255 0  0 0\n\
+             255\n255 255 0  0 255 255  255 0 255  127 127 127\n",
+        )
+        .unwrap();
+        assert_eq!((canvas.width, canvas.height), (4, 3));
+        assert_eq!(canvas[(0, 0)], color![1, 0.49804, 0]);
+        assert_eq!(canvas[(2, 0)], color![0.49804, 1, 0]);
+        assert_eq!(canvas[(3, 1)], color![0, 0, 1]);
+        assert_eq!(canvas[(3, 2)], color![0.49804, 0.49804, 0.49804]);
+
+        // Samples are scaled by the maximum value
+        let canvas = Canvas::from_ppm("P3\n2 1\n100\n100 100 100  50 50 50\n").unwrap();
+        assert_eq!(canvas[(1, 0)], color![0.5, 0.5, 0.5]);
+
+        // Missing or invalid samples are reported
+        assert_eq!(
+            Canvas::from_ppm("P3\n2 1\n255\n0 0 0 0 0\n").err(),
+            Some(PpmError::UnexpectedEnd)
+        );
+        assert_eq!(
+            Canvas::from_ppm("P3\n1 1\n255\n0 x 0\n").err(),
+            Some(PpmError::InvalidNumber("x".to_string()))
+        );
+
+        // Reading back a file written by `to_ppm`, split into 70-character lines
+        let ppm = format!("P3\n10 2\n255\n{}\n", "255 204 153 ".repeat(20));
+        let canvas = Canvas::from_ppm(&ppm).unwrap();
+        let result = Canvas::from_ppm(&canvas.to_ppm()).unwrap();
+        for width in 0..canvas.width {
+            for height in 0..canvas.height {
+                assert_eq!(result[(width, height)], canvas[(width, height)]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_ppm_binary() {
+        // Binary and ASCII PPM files hold the same pixels
+        let mut canvas = Canvas::new(5, 3);
+        canvas[(0, 0)] = color![1.5, 0, 0];
+        canvas[(2, 1)] = color![0, 0.5, 0];
+        canvas[(4, 2)] = color![-0.5, 0, 1];
+        let ppm = canvas.to_ppm_binary();
+        let header = b"P6\n5 3\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        let ascii: Vec<u8> = canvas
+            .to_ppm()
+            .split_whitespace()
+            .skip(4)
+            .map(|channel| channel.parse().unwrap())
+            .collect();
+        assert_eq!(&ppm[header.len()..], ascii.as_slice());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_write_png() {
+        // Writing a PNG image and decoding it back
+        let mut canvas = Canvas::new(5, 3);
+        canvas[(0, 0)] = color![1.5, 0, 0];
+        canvas[(2, 1)] = color![0, 0.5, 0];
+        canvas[(4, 2)] = color![-0.5, 0, 1];
+        let path = std::env::temp_dir().join("trace_test_write_png.png");
+        let path = path.to_str().unwrap();
+        canvas.write_png(path).unwrap();
+        let decoder = png::Decoder::new(File::open(path).unwrap());
+        let mut reader = decoder.read_info().unwrap();
+        let mut data = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut data).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!((info.width, info.height), (5, 3));
+        assert_eq!(info.color_type, png::ColorType::Rgb);
+        let pixel = |x: usize, y: usize| &data[(y * 5 + x) * 3..(y * 5 + x) * 3 + 3];
+        assert_eq!(pixel(0, 0), [255, 0, 0]);
+        assert_eq!(pixel(2, 1), [0, 128, 0]);
+        assert_eq!(pixel(4, 2), [0, 0, 255]);
+        assert_eq!(pixel(1, 1), [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_blit() {
+        let mut tile = Canvas::new(2, 3);
+        for y in 0..3 {
+            for x in 0..2 {
+                tile[(x, y)] = color![x as f64, y as f64, 1];
+            }
+        }
+        tile.set_alpha((1, 2), 0.5);
+
+        // A source within the canvas is copied whole
+        let mut canvas = Canvas::new(4, 4);
+        canvas.fill(color![0.5, 0.5, 0.5]);
+        canvas.blit(&tile, 1, 0);
+        for y in 0..4 {
+            for x in 0..4 {
+                let expected = match (x, y) {
+                    (1..=2, 0..=2) => color![(x - 1) as f64, y as f64, 1],
+                    _ => color![0.5, 0.5, 0.5],
+                };
+                assert_eq!(canvas[(x, y)], expected);
+            }
+        }
+        assert_eq!(canvas.alpha((2, 2)), 0.5);
+
+        // A source running past the edges is clipped
+        let mut canvas = Canvas::new(3, 3);
+        canvas.blit(&tile, 2, 1);
+        assert_eq!(canvas[(2, 1)], color![0, 0, 1]);
+        assert_eq!(canvas[(2, 2)], color![0, 1, 1]);
+        assert_eq!(canvas[(1, 2)], Color::BLACK);
+
+        // A source entirely outside is ignored
+        let mut canvas = Canvas::new(3, 3);
+        canvas.blit(&tile, 3, 0);
+        canvas.blit(&tile, 0, 10);
+        assert!(canvas.array.iter().all(|&pixel| pixel == Color::BLACK));
+    }
+
+    #[test]
+    fn test_tone_map_reinhard() {
+        // Bright pixels are brought below 1 instead of clipping to white
+        let mut canvas = Canvas::new(3, 1);
+        canvas[(0, 0)] = color![0.5, 1, 2];
+        canvas[(1, 0)] = color![4, 9, 99];
+        canvas[(2, 0)] = color![-1, 0, f64::INFINITY];
+        canvas.set_alpha((1, 0), 0.25);
+        let image = canvas.tone_map_reinhard();
+        assert_eq!(image[(0, 0)], color![1.0 / 3.0, 0.5, 2.0 / 3.0]);
+        assert_eq!(image[(1, 0)], color![0.8, 0.9, 0.99]);
+        assert_eq!(image[(2, 0)], color![0, 0, 1]);
+        assert_eq!(image.alpha((1, 0)), 0.25);
+        assert!(image.to_ppm().contains("204 230 252"));
+
+        // Exposure then tone mapping keeps highlights apart
+        let image = canvas.with_exposure(-1.0).tone_map_reinhard();
+        assert_eq!(image[(0, 0)], color![0.2, 1.0 / 3.0, 0.5]);
+    }
+
+    #[test]
+    fn test_over() {
+        // A render with a transparent background composited over a solid background
+        let world = World::default();
+        let mut camera = Camera::new(11, 11, std::f64::consts::PI / 2.0);
+        camera.transform =
+            Mat4::identity().view_transform(point![0, 0, -5], point![0, 0, 0], vector![0, 1, 0]);
+        let foreground = camera.render(&world);
+        let mut background = Canvas::new(11, 11);
+        for y in 0..background.height {
+            for x in 0..background.width {
+                background[(x, y)] = color![0, 0, 1];
+            }
+        }
+        let image = foreground.over(&background);
+        // the center pixel hits the outer sphere
+        assert_eq!(foreground.alpha((5, 5)), 1.0);
+        assert_eq!(image[(5, 5)], foreground[(5, 5)]);
+        // the corner pixel misses everything
+        assert_eq!(foreground.alpha((0, 0)), 0.0);
+        assert_eq!(image[(0, 0)], color![0, 0, 1]);
+        assert_eq!(image.alpha((0, 0)), 1.0);
     }
 }
@@ -1,7 +1,31 @@
 use crate::prelude::is_equal;
-use std::ops::{Add, Mul, Sub};
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Sub};
+
+/// Error returned by `Color::from_hex`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorError {
+    /// The string, without its `#`, is not 6 digits long.
+    InvalidLength(usize),
+    /// A character is not a hexadecimal digit.
+    InvalidDigit(char),
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidLength(length) => {
+                write!(f, "hex color must have 6 digits, found {}", length)
+            }
+            Self::InvalidDigit(digit) => write!(f, "invalid hex digit {:?}", digit),
+        }
+    }
+}
+
+impl std::error::Error for ColorError {}
 
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Color {
     pub red: f64,
     pub green: f64,
@@ -19,6 +43,135 @@ impl Color {
         green: 1.0,
         blue: 1.0,
     };
+
+    /// Replaces every channel that is not a finite number,
+    /// NaN becomes 0.0, positive infinity becomes 1.0,
+    /// and negative infinity becomes 0.0.
+    pub fn sanitize(&self) -> Color {
+        self.sanitize_with(0.0, 1.0)
+    }
+
+    /// Same as `sanitize`, with the replacements for NaN
+    /// and positive infinity given by `nan` and `infinity`.
+    pub fn sanitize_with(&self, nan: f64, infinity: f64) -> Color {
+        let channel = |value: f64| {
+            if value.is_nan() {
+                nan
+            } else if value == f64::INFINITY {
+                infinity
+            } else if value == f64::NEG_INFINITY {
+                0.0
+            } else {
+                value
+            }
+        };
+        Color {
+            red: channel(self.red),
+            green: channel(self.green),
+            blue: channel(self.blue),
+        }
+    }
+
+    /// Clamps each channel between 0.0 and 1.0.
+    pub fn clamp(&self) -> Color {
+        Color {
+            red: self.red.clamp(0.0, 1.0),
+            green: self.green.clamp(0.0, 1.0),
+            blue: self.blue.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Converts each channel, once clamped, to 0 through 255,
+    /// as written to PPM files. NaN channels become 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// assert_eq!(color![1.5, 0.5, -0.5].to_rgb8(), (255, 128, 0));
+    /// ```
+    pub fn to_rgb8(&self) -> (u8, u8, u8) {
+        let byte = |channel: f64| (channel * 255.0).round() as u8;
+        let color = self.clamp();
+        (byte(color.red), byte(color.green), byte(color.blue))
+    }
+
+    /// Parses a `#rrggbb` or `rrggbb` hex string,
+    /// dividing each byte by 255.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// assert_eq!(Color::from_hex("#ff8800"), Ok(color![1, 0.53333, 0]));
+    /// assert_eq!(Color::from_hex("FF8800"), Ok(color![1, 0.53333, 0]));
+    /// assert_eq!(Color::from_hex("#f80"), Err(ColorError::InvalidLength(3)));
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Color, ColorError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if let Some(digit) = digits.chars().find(|digit| !digit.is_ascii_hexdigit()) {
+            return Err(ColorError::InvalidDigit(digit));
+        }
+        if digits.len() != 6 {
+            return Err(ColorError::InvalidLength(digits.len()));
+        }
+        let byte = |index: usize| {
+            let byte = u8::from_str_radix(&digits[index..index + 2], 16).unwrap();
+            byte as f64 / 255.0
+        };
+        Ok(Color {
+            red: byte(0),
+            green: byte(2),
+            blue: byte(4),
+        })
+    }
+
+    /// Returns the `#rrggbb` hex string of the color,
+    /// converted as in `to_rgb8`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// assert_eq!(color![1, 0.53333, 1.5].to_hex(), "#ff88ff");
+    /// ```
+    pub fn to_hex(&self) -> String {
+        let (red, green, blue) = self.to_rgb8();
+        format!("#{:02x}{:02x}{:02x}", red, green, blue)
+    }
+
+    /// Linear interpolation, `self` at `t` = 0 and `other` at `t` = 1.
+    /// Neither `t` nor the channels are clamped,
+    /// values of `t` outside of [0, 1] extrapolate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// // Fading from black to white over frames
+    /// let frames = 5;
+    /// for frame in 0..frames {
+    ///     let t = frame as f64 / (frames - 1) as f64;
+    ///     let color = Color::BLACK.lerp(Color::WHITE, t);
+    ///     assert!(is_equal(color.red, t));
+    /// }
+    /// ```
+    pub fn lerp(&self, other: Self, t: f64) -> Self {
+        *self + (other - *self) * t
+    }
+
+    /// Returns the relative luminance, with the Rec. 709 weights.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// assert!(is_equal(Color::WHITE.luminance(), 1.0));
+    /// assert!(is_equal(color![0, 1, 0].luminance(), 0.7152));
+    /// ```
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.red + 0.7152 * self.green + 0.0722 * self.blue
+    }
 }
 
 /// Creates a Color containing the arguments.
@@ -65,6 +218,12 @@ impl Add for Color {
     }
 }
 
+impl AddAssign for Color {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
 impl Sub for Color {
     type Output = Self;
 
@@ -106,6 +265,22 @@ where
     }
 }
 
+impl<T> Div<T> for Color
+where
+    f64: From<T>,
+{
+    type Output = Self;
+
+    fn div(self, scalar: T) -> Self::Output {
+        let scalar = f64::from(scalar);
+        Self {
+            red: self.red / scalar,
+            green: self.green / scalar,
+            blue: self.blue / scalar,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -138,4 +313,89 @@ mod tests {
     fn test_mul() {
         assert_eq!(color![0.2, 0.3, 0.4] * 2, color![0.4, 0.6, 0.8]);
     }
+
+    #[test]
+    fn test_div() {
+        assert_eq!(color![0.4, 0.6, 0.8] / 2, color![0.2, 0.3, 0.4]);
+
+        // Averaging colors
+        let colors = [
+            color![1, 0, 0],
+            color![0, 1, 0],
+            color![0, 0, 1],
+            color![1, 1, 1],
+        ];
+        let mut sum = Color::BLACK;
+        for color in colors {
+            sum += color;
+        }
+        assert_eq!(sum, color![2, 2, 2]);
+        assert_eq!(sum / colors.len() as f64, color![0.5, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_lerp() {
+        let (a, b) = (color![1, 0.2, 0], color![0, 0.6, 1]);
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 0.5), color![0.5, 0.4, 0.5]);
+        assert_eq!(a.lerp(b, 1.0), b);
+    }
+
+    #[test]
+    fn test_clamp() {
+        assert_eq!(color![-0.5, 0.4, 1.7].clamp(), color![0, 0.4, 1]);
+        assert_eq!(Color::WHITE.clamp(), Color::WHITE);
+    }
+
+    #[test]
+    fn test_to_rgb8() {
+        let cases = [
+            (color![0, 0.5, 1], (0, 128, 255)),
+            (
+                color![0.499 / 255.0, 0.501 / 255.0, 254.6 / 255.0],
+                (0, 1, 255),
+            ),
+            (color![-1, 2, f64::NAN], (0, 255, 0)),
+        ];
+        for (color, rgb) in cases {
+            assert_eq!(color.to_rgb8(), rgb);
+        }
+    }
+
+    #[test]
+    fn test_hex() {
+        // Hex strings round trip
+        for hex in ["#000000", "#ffffff", "#0a7f3c", "#ff8800"] {
+            assert_eq!(Color::from_hex(hex).unwrap().to_hex(), hex);
+        }
+
+        // Bad lengths and characters are rejected
+        let cases = [
+            ("", ColorError::InvalidLength(0)),
+            ("#ff88001", ColorError::InvalidLength(7)),
+            ("##ff8800", ColorError::InvalidDigit('#')),
+            ("#ff88zz", ColorError::InvalidDigit('z')),
+            ("#ff 880", ColorError::InvalidDigit(' ')),
+            ("#ff88é", ColorError::InvalidDigit('é')),
+        ];
+        for (hex, error) in cases {
+            assert_eq!(Color::from_hex(hex), Err(error));
+        }
+    }
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(
+            color![f64::NAN, f64::INFINITY, 0.5].sanitize(),
+            color![0, 1, 0.5]
+        );
+        assert_eq!(
+            color![f64::NEG_INFINITY, -0.5, 1.5].sanitize(),
+            color![0, -0.5, 1.5]
+        );
+        assert_eq!(
+            color![f64::NAN, f64::INFINITY, 0.5].sanitize_with(0.5, 0.8),
+            color![0.5, 0.8, 0.5]
+        );
+    }
 }
@@ -0,0 +1,300 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::Intersection;
+use crate::mat4::Mat4;
+use crate::material::Material;
+use crate::point;
+use crate::point::Point;
+use crate::prelude::EPSILON;
+use crate::prelude::OBJECT_COUNTER;
+use crate::ray::Ray;
+use crate::shape::{Shape, Transforms};
+use crate::vector::Vector;
+use std::sync::atomic::Ordering;
+
+/// `Cone` instances are double-napped cones centered on the y axis,
+/// with their tips meeting at the world's origin (0, 0, 0).
+/// The radius of the cone at any `y` is `y.abs()`.
+/// Like `Cylinder`, they are infinite by default, may be truncated
+/// by `minimum` and `maximum`, and capped by setting `closed`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cone {
+    pub id: usize,
+    transforms: Transforms,
+    pub material: Material,
+    pub label: Option<String>,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Cone {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_transform(mut self, transform: Mat4) -> Self {
+        self.transforms.set_transform(transform);
+
+        self
+    }
+}
+
+impl Default for Cone {
+    fn default() -> Self {
+        Self {
+            id: OBJECT_COUNTER.fetch_add(1, Ordering::Relaxed),
+            transforms: Transforms::default(),
+            material: Material::new(),
+            label: None,
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+}
+
+/// Checks to see if the intersection at `t` is within
+/// `radius` from the y axis.
+fn check_cap(ray: Ray, t: f64, radius: f64) -> bool {
+    let x = ray.origin.x + t * ray.direction.x;
+    let z = ray.origin.z + t * ray.direction.z;
+    x.powi(2) + z.powi(2) <= radius.powi(2)
+}
+
+impl Cone {
+    fn intersect_caps<'a>(&'a self, ray: Ray, intersections: &mut Vec<Intersection<'a>>) {
+        // caps only matter if the cone is closed,
+        // and might possibly be intersected by the ray
+        if !self.closed || ray.direction.y.abs() < EPSILON {
+            return;
+        }
+        for cap in [self.minimum, self.maximum] {
+            let t = (cap - ray.origin.y) / ray.direction.y;
+            if check_cap(ray, t, cap.abs()) {
+                intersections.push(Intersection::new(t, self));
+            }
+        }
+    }
+
+    fn push_if_within<'a>(&'a self, ray: Ray, t: f64, intersections: &mut Vec<Intersection<'a>>) {
+        let y = ray.origin.y + t * ray.direction.y;
+        if self.minimum < y && y < self.maximum {
+            intersections.push(Intersection::new(t, self));
+        }
+    }
+}
+
+impl Shape for Cone {
+    fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection<'_>> {
+        let mut intersections = Vec::new();
+        self.local_intersect_into(local_ray, &mut intersections);
+        intersections
+    }
+
+    fn local_intersect_into<'s>(
+        &'s self,
+        local_ray: Ray,
+        intersections: &mut Vec<Intersection<'s>>,
+    ) {
+        let origin = local_ray.origin;
+        let direction = local_ray.direction;
+        let a = direction.x.powi(2) - direction.y.powi(2) + direction.z.powi(2);
+        let b = 2.0 * origin.x * direction.x - 2.0 * origin.y * direction.y
+            + 2.0 * origin.z * direction.z;
+        let c = origin.x.powi(2) - origin.y.powi(2) + origin.z.powi(2);
+        if a.abs() < EPSILON {
+            // ray is parallel to one of the cone's halves,
+            // so it meets the other half at most once
+            if b.abs() >= EPSILON {
+                self.push_if_within(local_ray, -c / (2.0 * b), intersections);
+            }
+        } else {
+            let discriminant = b.powi(2) - 4.0 * a * c;
+            if discriminant < 0.0 {
+                return;
+            }
+            let mut t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+            let mut t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            self.push_if_within(local_ray, t0, intersections);
+            self.push_if_within(local_ray, t1, intersections);
+        }
+        self.intersect_caps(local_ray, intersections);
+    }
+
+    fn local_normal_at(&self, local_point: Point) -> Vector {
+        let distance = local_point.x.powi(2) + local_point.z.powi(2);
+        if distance < self.maximum.powi(2) && local_point.y >= self.maximum - EPSILON {
+            Vector {
+                x: 0.0,
+                y: 1.0,
+                z: 0.0,
+            }
+        } else if distance < self.minimum.powi(2) && local_point.y <= self.minimum + EPSILON {
+            Vector {
+                x: 0.0,
+                y: -1.0,
+                z: 0.0,
+            }
+        } else {
+            let mut y = distance.sqrt();
+            if local_point.y > 0.0 {
+                y = -y;
+            }
+            Vector {
+                x: local_point.x,
+                y,
+                z: local_point.z,
+            }
+        }
+    }
+
+    /// The radius of a cone grows with `y`, so the box is as wide
+    /// as the cone at whichever end is farthest from the tip.
+    fn bounds(&self) -> BoundingBox {
+        let radius = self.minimum.abs().max(self.maximum.abs());
+        BoundingBox::new(
+            point![-radius, self.minimum, -radius],
+            point![radius, self.maximum, radius],
+        )
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn set_parent_transform(&mut self, transform: Mat4) {
+        self.transforms.set_parent(transform);
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn label_mut(&mut self) -> Option<&mut Option<String>> {
+        Some(&mut self.label)
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    fn debug(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::is_equal;
+    use crate::{point, vector};
+    use std::f64::consts::SQRT_2;
+
+    #[test]
+    fn test_local_intersect() {
+        // Intersecting a cone with a ray
+        let cone = Cone::new();
+        let cases = [
+            (point![0, 0, -5], vector![0, 0, 1], 5.0, 5.0),
+            (point![0, 0, -5], vector![1, 1, 1], 8.66025, 8.66025),
+            (point![1, 1, -5], vector![-0.5, -1, 1], 4.55006, 49.44994),
+        ];
+        for (origin, direction, t0, t1) in cases {
+            let ray = Ray {
+                origin,
+                direction: direction.normalize(),
+            };
+            let intersections = cone.local_intersect(ray);
+            assert_eq!(intersections.len(), 2);
+            assert!(is_equal(intersections[0].t, t0));
+            assert!(is_equal(intersections[1].t, t1));
+        }
+
+        // Intersecting a cone with a ray parallel to one of its halves
+        let cone = Cone::new();
+        let ray = Ray {
+            origin: point![0, 0, -1],
+            direction: vector![0, 1, 1].normalize(),
+        };
+        let intersections = cone.local_intersect(ray);
+        assert_eq!(intersections.len(), 1);
+        assert!(is_equal(intersections[0].t, 0.35355));
+
+        // Intersecting a cone's end caps
+        let cone = Cone {
+            minimum: -0.5,
+            maximum: 0.5,
+            closed: true,
+            ..Default::default()
+        };
+        let cases = [
+            (point![0, 0, -5], vector![0, 1, 0], 0),
+            (point![0, 0, -0.25], vector![0, 1, 1], 2),
+            (point![0, 0, -0.25], vector![0, 1, 0], 4),
+        ];
+        for (origin, direction, count) in cases {
+            let ray = Ray {
+                origin,
+                direction: direction.normalize(),
+            };
+            assert_eq!(cone.local_intersect(ray).len(), count);
+        }
+    }
+
+    #[test]
+    fn test_local_normal_at() {
+        // Computing the normal vector on a cone
+        let cone = Cone::new();
+        let cases = [
+            (point![0, 0, 0], vector![0, 0, 0]),
+            (point![1, 1, 1], vector![1, -SQRT_2, 1]),
+            (point![-1, -1, 0], vector![-1, 1, 0]),
+        ];
+        for (point, normal) in cases {
+            assert_eq!(cone.local_normal_at(point), normal);
+        }
+
+        // The normal vector on a cone's end caps
+        let cone = Cone {
+            minimum: -1.0,
+            maximum: 2.0,
+            closed: true,
+            ..Default::default()
+        };
+        assert_eq!(cone.local_normal_at(point![0.5, 2, 0]), vector![0, 1, 0]);
+        assert_eq!(cone.local_normal_at(point![0.5, -1, 0]), vector![0, -1, 0]);
+    }
+
+    #[test]
+    fn test_bounds() {
+        // An unbounded cone has an infinite bounding box
+        let bounds = Cone::new().bounds();
+        assert_eq!(bounds.min.x, f64::NEG_INFINITY);
+        assert_eq!(bounds.max.y, f64::INFINITY);
+
+        // A bounded cone is as wide as its widest end
+        let cone = Cone {
+            minimum: -5.0,
+            maximum: 3.0,
+            ..Default::default()
+        };
+        let bounds = cone.bounds();
+        assert_eq!(bounds.min, point![-5, -5, -5]);
+        assert_eq!(bounds.max, point![5, 3, 5]);
+    }
+}
@@ -0,0 +1,345 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::Intersection;
+use crate::mat4::Mat4;
+use crate::material::Material;
+use crate::point::Point;
+use crate::prelude::OBJECT_COUNTER;
+use crate::ray::Ray;
+use crate::shape::{Shape, Transforms};
+use crate::vector::Vector;
+use std::sync::atomic::Ordering;
+
+/// Set operation combining the two shapes of a `Csg`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// Keeps the outside of both shapes.
+    Union,
+    /// Keeps where both shapes overlap.
+    Intersection,
+    /// Keeps the left shape, minus where the right shape overlaps it.
+    Difference,
+}
+
+/// Whether a hit is part of the surface of a `Csg`, given whether
+/// it hits the left shape, and whether it lies inside the left
+/// and right shapes.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// # use trace::csg::intersection_allowed;
+/// // A hit on the left shape, outside of the right shape, is kept by a union
+/// assert!(intersection_allowed(Operation::Union, true, false, false));
+/// // but not by an intersection
+/// assert!(!intersection_allowed(Operation::Intersection, true, false, false));
+/// ```
+pub fn intersection_allowed(
+    operation: Operation,
+    left_hit: bool,
+    inside_left: bool,
+    inside_right: bool,
+) -> bool {
+    match operation {
+        Operation::Union => (left_hit && !inside_right) || (!left_hit && !inside_left),
+        Operation::Intersection => (left_hit && inside_right) || (!left_hit && inside_left),
+        Operation::Difference => (left_hit && !inside_right) || (!left_hit && inside_left),
+    }
+}
+
+/// `Csg` instances are constructive solid geometry shapes,
+/// made by combining two shapes with an `Operation`.
+#[derive(Debug, Clone)]
+pub struct Csg {
+    pub id: usize,
+    transforms: Transforms,
+    pub material: Material,
+    pub label: Option<String>,
+    pub operation: Operation,
+    left: Box<dyn Shape>,
+    right: Box<dyn Shape>,
+}
+
+impl Csg {
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// // A sphere with a bite taken out of it
+    /// let csg = Csg::new(
+    ///     Operation::Difference,
+    ///     Sphere::new(),
+    ///     Cube::new().set_transform(Mat4::identity().translate(1, 1, -1)),
+    /// );
+    /// assert_eq!(csg.operation, Operation::Difference);
+    /// ```
+    pub fn new<L, R>(operation: Operation, mut left: L, mut right: R) -> Self
+    where
+        L: Shape + 'static,
+        R: Shape + 'static,
+    {
+        left.set_parent_transform(Mat4::identity());
+        right.set_parent_transform(Mat4::identity());
+        Self {
+            id: OBJECT_COUNTER.fetch_add(1, Ordering::Relaxed),
+            transforms: Transforms::default(),
+            material: Material::new(),
+            label: None,
+            operation,
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    /// Sets the transform of the shape, which is combined
+    /// into the parent transform of both of its children.
+    pub fn set_transform(mut self, transform: Mat4) -> Self {
+        self.transforms.set_transform(transform);
+        self.update_children();
+
+        self
+    }
+
+    pub fn left(&self) -> &dyn Shape {
+        self.left.as_ref()
+    }
+
+    pub fn right(&self) -> &dyn Shape {
+        self.right.as_ref()
+    }
+
+    /// Keeps only the intersections on the surface of the combined shape,
+    /// `intersections` must be sorted.
+    pub fn filter_intersections<'a>(
+        &self,
+        intersections: Vec<Intersection<'a>>,
+    ) -> Vec<Intersection<'a>> {
+        // begin outside of both children
+        let mut inside_left = false;
+        let mut inside_right = false;
+        let mut result = Vec::new();
+        for intersection in intersections {
+            let left_hit = self.left.includes(intersection.object);
+            if intersection_allowed(self.operation, left_hit, inside_left, inside_right) {
+                result.push(intersection);
+            }
+            // crossing the surface of a child toggles whether we are inside it
+            if left_hit {
+                inside_left = !inside_left;
+            } else {
+                inside_right = !inside_right;
+            }
+        }
+        result
+    }
+
+    fn update_children(&mut self) {
+        let world_transform = self.transforms.world();
+        self.left.set_parent_transform(world_transform);
+        self.right.set_parent_transform(world_transform);
+    }
+}
+
+impl Shape for Csg {
+    fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection<'_>> {
+        let mut intersections = Vec::new();
+        self.left.intersect_into(local_ray, &mut intersections);
+        self.right.intersect_into(local_ray, &mut intersections);
+        intersections.sort_by(|a, b| a.t.total_cmp(&b.t));
+        self.filter_intersections(intersections)
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.id == other.id() || self.left.includes(other) || self.right.includes(other)
+    }
+
+    /// Like groups, CSG shapes have no surface of their own.
+    fn local_normal_at(&self, _local_point: Point) -> Vector {
+        panic!("local_normal_at called on a CSG shape");
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = self.left.parent_space_bounds();
+        bounds.add_box(self.right.parent_space_bounds());
+        bounds
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn set_parent_transform(&mut self, transform: Mat4) {
+        self.transforms.set_parent(transform);
+        self.update_children();
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn label_mut(&mut self) -> Option<&mut Option<String>> {
+        Some(&mut self.label)
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    fn debug(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::Cube;
+    use crate::prelude::is_equal;
+    use crate::sphere::Sphere;
+    use crate::{point, vector};
+
+    #[test]
+    fn test_intersection_allowed() {
+        // Evaluating the rule for a CSG operation
+        let cases = [
+            (Operation::Union, true, true, true, false),
+            (Operation::Union, true, true, false, true),
+            (Operation::Union, true, false, true, false),
+            (Operation::Union, true, false, false, true),
+            (Operation::Union, false, true, true, false),
+            (Operation::Union, false, true, false, false),
+            (Operation::Union, false, false, true, true),
+            (Operation::Union, false, false, false, true),
+            (Operation::Intersection, true, true, true, true),
+            (Operation::Intersection, true, true, false, false),
+            (Operation::Intersection, true, false, true, true),
+            (Operation::Intersection, true, false, false, false),
+            (Operation::Intersection, false, true, true, true),
+            (Operation::Intersection, false, true, false, true),
+            (Operation::Intersection, false, false, true, false),
+            (Operation::Intersection, false, false, false, false),
+            (Operation::Difference, true, true, true, false),
+            (Operation::Difference, true, true, false, true),
+            (Operation::Difference, true, false, true, false),
+            (Operation::Difference, true, false, false, true),
+            (Operation::Difference, false, true, true, true),
+            (Operation::Difference, false, true, false, true),
+            (Operation::Difference, false, false, true, false),
+            (Operation::Difference, false, false, false, false),
+        ];
+        for (operation, left_hit, inside_left, inside_right, result) in cases {
+            assert_eq!(
+                intersection_allowed(operation, left_hit, inside_left, inside_right),
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn test_filter_intersections() {
+        // Filtering a list of intersections
+        let cases = [
+            (Operation::Union, 0, 3),
+            (Operation::Intersection, 1, 2),
+            (Operation::Difference, 0, 1),
+        ];
+        for (operation, x0, x1) in cases {
+            let csg = Csg::new(operation, Sphere::new(), Cube::new());
+            let intersections = vec![
+                Intersection::new(1.0, csg.left()),
+                Intersection::new(2.0, csg.right()),
+                Intersection::new(3.0, csg.left()),
+                Intersection::new(4.0, csg.right()),
+            ];
+            let expected = [intersections[x0].t, intersections[x1].t];
+            let result = csg.filter_intersections(intersections);
+            assert_eq!(result.len(), 2);
+            assert!(is_equal(result[0].t, expected[0]));
+            assert!(is_equal(result[1].t, expected[1]));
+        }
+    }
+
+    #[test]
+    fn test_local_intersect() {
+        // A ray misses a CSG object
+        let csg = Csg::new(Operation::Union, Sphere::new(), Cube::new());
+        let ray = Ray {
+            origin: point![0, 2, -5],
+            direction: vector![0, 0, 1],
+        };
+        assert!(csg.local_intersect(ray).is_empty());
+
+        // A ray hits a CSG object
+        let csg = Csg::new(
+            Operation::Union,
+            Sphere::new(),
+            Sphere::new().set_transform(Mat4::identity().translate(0, 0, 0.5)),
+        );
+        let ray = Ray {
+            origin: point![0, 0, -5],
+            direction: vector![0, 0, 1],
+        };
+        let intersections = csg.local_intersect(ray);
+        assert_eq!(intersections.len(), 2);
+        assert!(is_equal(intersections[0].t, 4.0));
+        assert_eq!(intersections[0].object.id(), csg.left().id());
+        assert!(is_equal(intersections[1].t, 6.5));
+        assert_eq!(intersections[1].object.id(), csg.right().id());
+
+        // A sphere with a cube-shaped bite taken out of it
+        let csg = Csg::new(
+            Operation::Difference,
+            Sphere::new(),
+            Cube::new().set_transform(Mat4::identity().translate(0, 0, -1.5)),
+        );
+        let intersections = csg.local_intersect(ray);
+        assert_eq!(intersections.len(), 2);
+        assert!(is_equal(intersections[0].t, 4.5));
+        assert_eq!(intersections[0].object.id(), csg.right().id());
+        assert!(is_equal(intersections[1].t, 6.0));
+        assert_eq!(intersections[1].object.id(), csg.left().id());
+
+        // A lens formed by intersecting two spheres
+        let csg = Csg::new(
+            Operation::Intersection,
+            Sphere::new().set_transform(Mat4::identity().translate(0, 0, -0.5)),
+            Sphere::new().set_transform(Mat4::identity().translate(0, 0, 0.5)),
+        );
+        let intersections = csg.local_intersect(ray);
+        assert_eq!(intersections.len(), 2);
+        assert!(is_equal(intersections[0].t, 4.5));
+        assert_eq!(intersections[0].object.id(), csg.right().id());
+        assert!(is_equal(intersections[1].t, 5.5));
+        assert_eq!(intersections[1].object.id(), csg.left().id());
+    }
+
+    #[test]
+    fn test_normal_at() {
+        // Normals of a transformed CSG object are computed on its children
+        let csg = Csg::new(Operation::Union, Sphere::new(), Cube::new())
+            .set_transform(Mat4::identity().translate(0, 0, 5));
+        let ray = Ray {
+            origin: point![0, 0, 0],
+            direction: vector![0, 0, 1],
+        };
+        let intersections = csg.intersect(ray);
+        assert_eq!(intersections.len(), 2);
+        assert!(is_equal(intersections[0].t, 4.0));
+        assert_eq!(
+            intersections[0].object.normal_at(point![0, 0, 4]),
+            vector![0, 0, -1]
+        );
+    }
+}
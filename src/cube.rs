@@ -0,0 +1,244 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::Intersection;
+use crate::mat4::Mat4;
+use crate::material::Material;
+use crate::point;
+use crate::point::Point;
+use crate::prelude::EPSILON;
+use crate::prelude::OBJECT_COUNTER;
+use crate::ray::Ray;
+use crate::shape::{Shape, Transforms};
+use crate::vector::Vector;
+use std::sync::atomic::Ordering;
+
+/// `Cube` instances are axis-aligned boxes situated at the world's
+/// origin (0, 0, 0), extending from -1 to 1 on each axis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cube {
+    pub id: usize,
+    transforms: Transforms,
+    pub material: Material,
+    pub label: Option<String>,
+}
+
+impl Cube {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_transform(mut self, transform: Mat4) -> Self {
+        self.transforms.set_transform(transform);
+
+        self
+    }
+}
+
+impl Default for Cube {
+    fn default() -> Self {
+        Self {
+            id: OBJECT_COUNTER.fetch_add(1, Ordering::Relaxed),
+            transforms: Transforms::default(),
+            material: Material::new(),
+            label: None,
+        }
+    }
+}
+
+/// Returns the `t` values where the ray crosses the two planes
+/// of a slab, at -1 and 1 on a single axis.
+fn check_axis(origin: f64, direction: f64) -> (f64, f64) {
+    let tmin_numerator = -1.0 - origin;
+    let tmax_numerator = 1.0 - origin;
+    let (tmin, tmax) = if direction.abs() >= EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (
+            tmin_numerator * f64::INFINITY,
+            tmax_numerator * f64::INFINITY,
+        )
+    };
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+impl Shape for Cube {
+    fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection<'_>> {
+        let mut intersections = Vec::new();
+        self.local_intersect_into(local_ray, &mut intersections);
+        intersections
+    }
+
+    fn local_intersect_into<'s>(
+        &'s self,
+        local_ray: Ray,
+        intersections: &mut Vec<Intersection<'s>>,
+    ) {
+        let (xtmin, xtmax) = check_axis(local_ray.origin.x, local_ray.direction.x);
+        let (ytmin, ytmax) = check_axis(local_ray.origin.y, local_ray.direction.y);
+        let (ztmin, ztmax) = check_axis(local_ray.origin.z, local_ray.direction.z);
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+        if tmin > tmax {
+            return;
+        }
+        intersections.push(Intersection::new(tmin, self));
+        intersections.push(Intersection::new(tmax, self));
+    }
+
+    /// The normal points along the axis of the component
+    /// with the largest absolute value.
+    fn local_normal_at(&self, local_point: Point) -> Vector {
+        let max = local_point
+            .x
+            .abs()
+            .max(local_point.y.abs())
+            .max(local_point.z.abs());
+        if max == local_point.x.abs() {
+            Vector {
+                x: local_point.x,
+                y: 0.0,
+                z: 0.0,
+            }
+        } else if max == local_point.y.abs() {
+            Vector {
+                x: 0.0,
+                y: local_point.y,
+                z: 0.0,
+            }
+        } else {
+            Vector {
+                x: 0.0,
+                y: 0.0,
+                z: local_point.z,
+            }
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(point![-1, -1, -1], point![1, 1, 1])
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn set_parent_transform(&mut self, transform: Mat4) {
+        self.transforms.set_parent(transform);
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn label_mut(&mut self) -> Option<&mut Option<String>> {
+        Some(&mut self.label)
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    fn debug(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color;
+    use crate::color::Color;
+    use crate::pattern::stripe::Stripe;
+    use crate::prelude::is_equal;
+    use crate::shape::ShapeBuilder;
+    use crate::{point, vector};
+
+    #[test]
+    fn test_builder() {
+        // A cube configured in a single expression
+        let cube = Cube::new()
+            .set_transform(Mat4::identity().translate(0, 1, 0))
+            .set_material(
+                Material::new()
+                    .with_color(color![1, 0, 0])
+                    .with_shininess(50.0),
+            )
+            .set_pattern(Box::new(Stripe::new(Color::WHITE, Color::BLACK)));
+        assert_eq!(cube.transform(), &Mat4::identity().translate(0, 1, 0));
+        assert_eq!(cube.material().color, color![1, 0, 0]);
+        assert_eq!(cube.material().shininess, 50.0);
+        let pattern = cube.material().pattern.as_ref().unwrap();
+        assert_eq!(pattern.at_object(&cube, point![1.5, 1, 0]), Color::BLACK);
+    }
+
+    #[test]
+    fn test_local_intersect() {
+        // A ray intersects a cube
+        let cube = Cube::new();
+        let cases = [
+            (point![5, 0.5, 0], vector![-1, 0, 0], 4.0, 6.0),
+            (point![-5, 0.5, 0], vector![1, 0, 0], 4.0, 6.0),
+            (point![0.5, 5, 0], vector![0, -1, 0], 4.0, 6.0),
+            (point![0.5, -5, 0], vector![0, 1, 0], 4.0, 6.0),
+            (point![0.5, 0, 5], vector![0, 0, -1], 4.0, 6.0),
+            (point![0.5, 0, -5], vector![0, 0, 1], 4.0, 6.0),
+            (point![0, 0.5, 0], vector![0, 0, 1], -1.0, 1.0),
+        ];
+        for (origin, direction, t1, t2) in cases {
+            let ray = Ray { origin, direction };
+            let intersections = cube.local_intersect(ray);
+            assert_eq!(intersections.len(), 2);
+            assert!(is_equal(intersections[0].t, t1));
+            assert!(is_equal(intersections[1].t, t2));
+        }
+
+        // A ray misses a cube
+        let cube = Cube::new();
+        let cases = [
+            (point![-2, 0, 0], vector![0.2673, 0.5345, 0.8018]),
+            (point![0, -2, 0], vector![0.8018, 0.2673, 0.5345]),
+            (point![0, 0, -2], vector![0.5345, 0.8018, 0.2673]),
+            (point![2, 0, 2], vector![0, 0, -1]),
+            (point![0, 2, 2], vector![0, -1, 0]),
+            (point![2, 2, 0], vector![-1, 0, 0]),
+        ];
+        for (origin, direction) in cases {
+            let ray = Ray { origin, direction };
+            assert!(cube.local_intersect(ray).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_local_normal_at() {
+        // The normal on the surface of a cube
+        let cube = Cube::new();
+        let cases = [
+            (point![1, 0.5, -0.8], vector![1, 0, 0]),
+            (point![-1, -0.2, 0.9], vector![-1, 0, 0]),
+            (point![-0.4, 1, -0.1], vector![0, 1, 0]),
+            (point![0.3, -1, -0.7], vector![0, -1, 0]),
+            (point![-0.6, 0.3, 1], vector![0, 0, 1]),
+            (point![0.4, 0.4, -1], vector![0, 0, -1]),
+            (point![1, 1, 1], vector![1, 0, 0]),
+            (point![-1, -1, -1], vector![-1, 0, 0]),
+        ];
+        for (point, normal) in cases {
+            assert_eq!(cube.local_normal_at(point), normal);
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::Intersection;
+use crate::mat4::Mat4;
+use crate::material::Material;
+use crate::point;
+use crate::point::Point;
+use crate::prelude::EPSILON;
+use crate::prelude::OBJECT_COUNTER;
+use crate::ray::Ray;
+use crate::shape::{Shape, Transforms};
+use crate::vector::Vector;
+use std::sync::atomic::Ordering;
+
+/// `Cylinder` instances are centered on the y axis, with radius of 1.
+/// They are infinite by default, but may be truncated
+/// by `minimum` and `maximum`, both exclusive.
+/// A truncated cylinder is hollow unless `closed` is set,
+/// which caps both of its ends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cylinder {
+    pub id: usize,
+    transforms: Transforms,
+    pub material: Material,
+    pub label: Option<String>,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Cylinder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_transform(mut self, transform: Mat4) -> Self {
+        self.transforms.set_transform(transform);
+
+        self
+    }
+}
+
+impl Default for Cylinder {
+    fn default() -> Self {
+        Self {
+            id: OBJECT_COUNTER.fetch_add(1, Ordering::Relaxed),
+            transforms: Transforms::default(),
+            material: Material::new(),
+            label: None,
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+}
+
+/// Checks to see if the intersection at `t` is within
+/// a radius of 1 from the y axis.
+fn check_cap(ray: Ray, t: f64) -> bool {
+    let x = ray.origin.x + t * ray.direction.x;
+    let z = ray.origin.z + t * ray.direction.z;
+    x.powi(2) + z.powi(2) <= 1.0
+}
+
+impl Cylinder {
+    fn intersect_caps<'a>(&'a self, ray: Ray, intersections: &mut Vec<Intersection<'a>>) {
+        // caps only matter if the cylinder is closed,
+        // and might possibly be intersected by the ray
+        if !self.closed || ray.direction.y.abs() < EPSILON {
+            return;
+        }
+        for cap in [self.minimum, self.maximum] {
+            let t = (cap - ray.origin.y) / ray.direction.y;
+            if check_cap(ray, t) {
+                intersections.push(Intersection::new(t, self));
+            }
+        }
+    }
+}
+
+impl Shape for Cylinder {
+    fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection<'_>> {
+        let mut intersections = Vec::new();
+        self.local_intersect_into(local_ray, &mut intersections);
+        intersections
+    }
+
+    fn local_intersect_into<'s>(
+        &'s self,
+        local_ray: Ray,
+        intersections: &mut Vec<Intersection<'s>>,
+    ) {
+        let a = local_ray.direction.x.powi(2) + local_ray.direction.z.powi(2);
+        // ray is parallel to the y axis, so it never meets the wall
+        if a.abs() < EPSILON {
+            self.intersect_caps(local_ray, intersections);
+            return;
+        }
+        let b = 2.0 * local_ray.origin.x * local_ray.direction.x
+            + 2.0 * local_ray.origin.z * local_ray.direction.z;
+        let c = local_ray.origin.x.powi(2) + local_ray.origin.z.powi(2) - 1.0;
+        let discriminant = b.powi(2) - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return;
+        }
+        let mut t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+        let mut t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+        if t0 > t1 {
+            std::mem::swap(&mut t0, &mut t1);
+        }
+        for t in [t0, t1] {
+            let y = local_ray.origin.y + t * local_ray.direction.y;
+            if self.minimum < y && y < self.maximum {
+                intersections.push(Intersection::new(t, self));
+            }
+        }
+        self.intersect_caps(local_ray, intersections);
+    }
+
+    fn local_normal_at(&self, local_point: Point) -> Vector {
+        let distance = local_point.x.powi(2) + local_point.z.powi(2);
+        if distance < 1.0 && local_point.y >= self.maximum - EPSILON {
+            Vector {
+                x: 0.0,
+                y: 1.0,
+                z: 0.0,
+            }
+        } else if distance < 1.0 && local_point.y <= self.minimum + EPSILON {
+            Vector {
+                x: 0.0,
+                y: -1.0,
+                z: 0.0,
+            }
+        } else {
+            Vector {
+                x: local_point.x,
+                y: 0.0,
+                z: local_point.z,
+            }
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(point![-1, self.minimum, -1], point![1, self.maximum, 1])
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn set_parent_transform(&mut self, transform: Mat4) {
+        self.transforms.set_parent(transform);
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn label_mut(&mut self) -> Option<&mut Option<String>> {
+        Some(&mut self.label)
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    fn debug(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::is_equal;
+    use crate::{point, vector};
+
+    #[test]
+    fn test_local_intersect() {
+        // A ray misses a cylinder
+        let cylinder = Cylinder::new();
+        let cases = [
+            (point![1, 0, 0], vector![0, 1, 0]),
+            (point![0, 0, 0], vector![0, 1, 0]),
+            (point![0, 0, -5], vector![1, 1, 1]),
+        ];
+        for (origin, direction) in cases {
+            let ray = Ray {
+                origin,
+                direction: direction.normalize(),
+            };
+            assert!(cylinder.local_intersect(ray).is_empty());
+        }
+
+        // A ray strikes a cylinder
+        let cylinder = Cylinder::new();
+        let cases = [
+            (point![1, 0, -5], vector![0, 0, 1], 5.0, 5.0),
+            (point![0, 0, -5], vector![0, 0, 1], 4.0, 6.0),
+            (point![0.5, 0, -5], vector![0.1, 1, 1], 6.80798, 7.08872),
+        ];
+        for (origin, direction, t0, t1) in cases {
+            let ray = Ray {
+                origin,
+                direction: direction.normalize(),
+            };
+            let intersections = cylinder.local_intersect(ray);
+            assert_eq!(intersections.len(), 2);
+            assert!(is_equal(intersections[0].t, t0));
+            assert!(is_equal(intersections[1].t, t1));
+        }
+
+        // Intersecting a constrained cylinder
+        let cylinder = Cylinder {
+            minimum: 1.0,
+            maximum: 2.0,
+            ..Default::default()
+        };
+        let cases = [
+            (point![0, 1.5, 0], vector![0.1, 1, 0], 0),
+            (point![0, 3, -5], vector![0, 0, 1], 0),
+            (point![0, 0, -5], vector![0, 0, 1], 0),
+            (point![0, 2, -5], vector![0, 0, 1], 0),
+            (point![0, 1, -5], vector![0, 0, 1], 0),
+            (point![0, 1.5, -2], vector![0, 0, 1], 2),
+        ];
+        for (origin, direction, count) in cases {
+            let ray = Ray {
+                origin,
+                direction: direction.normalize(),
+            };
+            assert_eq!(cylinder.local_intersect(ray).len(), count);
+        }
+
+        // Intersecting the caps of a closed cylinder
+        let cylinder = Cylinder {
+            minimum: 1.0,
+            maximum: 2.0,
+            closed: true,
+            ..Default::default()
+        };
+        let cases = [
+            (point![0, 3, 0], vector![0, -1, 0], 2),
+            (point![0, 3, -2], vector![0, -1, 2], 2),
+            (point![0, 4, -2], vector![0, -1, 1], 2),
+            (point![0, 0, -2], vector![0, 1, 2], 2),
+            (point![0, -1, -2], vector![0, 1, 1], 2),
+        ];
+        for (origin, direction, count) in cases {
+            let ray = Ray {
+                origin,
+                direction: direction.normalize(),
+            };
+            assert_eq!(cylinder.local_intersect(ray).len(), count);
+        }
+    }
+
+    #[test]
+    fn test_local_normal_at() {
+        // Normal vector on a cylinder
+        let cylinder = Cylinder::new();
+        let cases = [
+            (point![1, 0, 0], vector![1, 0, 0]),
+            (point![0, 5, -1], vector![0, 0, -1]),
+            (point![0, -2, 1], vector![0, 0, 1]),
+            (point![-1, 1, 0], vector![-1, 0, 0]),
+        ];
+        for (point, normal) in cases {
+            assert_eq!(cylinder.local_normal_at(point), normal);
+        }
+
+        // The default minimum and maximum for a cylinder
+        let cylinder = Cylinder::new();
+        assert_eq!(cylinder.minimum, f64::NEG_INFINITY);
+        assert_eq!(cylinder.maximum, f64::INFINITY);
+
+        // The default closed value for a cylinder
+        let cylinder = Cylinder::new();
+        assert!(!cylinder.closed);
+
+        // The normal vector on a cylinder's end caps
+        let cylinder = Cylinder {
+            minimum: 1.0,
+            maximum: 2.0,
+            closed: true,
+            ..Default::default()
+        };
+        let cases = [
+            (point![0, 1, 0], vector![0, -1, 0]),
+            (point![0.5, 1, 0], vector![0, -1, 0]),
+            (point![0, 1, 0.5], vector![0, -1, 0]),
+            (point![0, 2, 0], vector![0, 1, 0]),
+            (point![0.5, 2, 0], vector![0, 1, 0]),
+            (point![0, 2, 0.5], vector![0, 1, 0]),
+        ];
+        for (point, normal) in cases {
+            assert_eq!(cylinder.local_normal_at(point), normal);
+        }
+    }
+}
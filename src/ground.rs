@@ -0,0 +1,156 @@
+use crate::bounds::BoundingBox;
+use crate::color::Color;
+use crate::intersection::Intersection;
+use crate::mat4::Mat4;
+use crate::material::Material;
+use crate::pattern::checkers::Checkers;
+use crate::plane::Plane;
+use crate::point::Point;
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeBuilder, Transforms};
+use crate::vector::Vector;
+
+/// An infinite checkered floor: a `Plane` with a `Checkers` pattern.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// let mut ground = Ground::new(color![0.15, 0.15, 0.15], color![0.5, 0.5, 0.5], 1.7);
+/// ground.material_mut().specular = 0.0;
+/// assert!(ground.material().pattern.is_some());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ground {
+    plane: Plane,
+}
+
+impl Ground {
+    /// Creates a ground checkered with `a` and `b`,
+    /// each square having a side length of `scale`.
+    pub fn new(a: Color, b: Color, scale: f64) -> Self {
+        let plane = Plane::new().set_pattern(Box::new(
+            Checkers::new(a, b).set_transform(Mat4::identity().uniform_scale(scale)),
+        ));
+        Self { plane }
+    }
+
+    pub fn set_transform(mut self, transform: Mat4) -> Self {
+        self.plane = self.plane.set_transform(transform);
+
+        self
+    }
+}
+
+impl Default for Ground {
+    fn default() -> Self {
+        Self::new(Color::WHITE, Color::BLACK, 1.0)
+    }
+}
+
+impl Shape for Ground {
+    fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection<'_>> {
+        self.plane.local_intersect(local_ray)
+    }
+
+    fn local_intersect_into<'s>(
+        &'s self,
+        local_ray: Ray,
+        intersections: &mut Vec<Intersection<'s>>,
+    ) {
+        self.plane.local_intersect_into(local_ray, intersections);
+    }
+
+    fn local_normal_at(&self, local_point: Point) -> Vector {
+        self.plane.local_normal_at(local_point)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.plane.bounds()
+    }
+
+    fn transforms(&self) -> &Transforms {
+        self.plane.transforms()
+    }
+
+    fn set_parent_transform(&mut self, transform: Mat4) {
+        self.plane.set_parent_transform(transform);
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.plane.label()
+    }
+
+    fn label_mut(&mut self) -> Option<&mut Option<String>> {
+        self.plane.label_mut()
+    }
+
+    fn material(&self) -> &Material {
+        self.plane.material()
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        self.plane.material_mut()
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    fn debug(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn id(&self) -> usize {
+        self.plane.id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::light::Light;
+    use crate::world::World;
+    use crate::{color, point, vector};
+
+    #[test]
+    fn test_color_at() {
+        // A ray hitting the ground returns a checker color depending on position
+        let mut ground = Ground::new(color![0.15, 0.15, 0.15], color![0.5, 0.5, 0.5], 1.0);
+        ground.material_mut().ambient = 1.0;
+        ground.material_mut().diffuse = 0.0;
+        ground.material_mut().specular = 0.0;
+        let mut world = World::new(Light::point(point![0, 10, 0], color![1, 1, 1]));
+        world.push(ground);
+        let ray = Ray {
+            origin: point![0.5, 1, 0.5],
+            direction: vector![0, -1, 0],
+        };
+        assert_eq!(world.color_at(ray), color![0.15, 0.15, 0.15]);
+        let ray = Ray {
+            origin: point![1.5, 1, 0.5],
+            direction: vector![0, -1, 0],
+        };
+        assert_eq!(world.color_at(ray), color![0.5, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_label() {
+        // A ground is labelled through its plane
+        let ground = Ground::default();
+        assert_eq!(ground.label(), None);
+        let ground = ground.set_label("floor");
+        assert_eq!(ground.label(), Some("floor"));
+        assert_eq!(ground.plane.label(), Some("floor"));
+        assert!(ground.debug().contains("label: Some(\"floor\")"));
+
+        // Boxed copies keep the label, along with the id
+        let copy = ground.clone_box();
+        assert_eq!(copy.label(), Some("floor"));
+        assert_eq!(copy.id(), ground.id());
+
+        // Labelling again replaces the label
+        let ground = ground.set_label("ground");
+        assert_eq!(ground.label(), Some("ground"));
+    }
+}
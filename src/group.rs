@@ -0,0 +1,369 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::Intersection;
+use crate::mat4::Mat4;
+use crate::material::Material;
+use crate::point::Point;
+use crate::prelude::OBJECT_COUNTER;
+use crate::ray::Ray;
+use crate::shape::{Shape, Transforms};
+use crate::vector::Vector;
+use std::sync::atomic::Ordering;
+
+/// `Group` instances are collections of shapes transformed as a unit.
+/// A ray is transformed into group space once, then intersected
+/// with every child, each applying its own transform on top.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    pub id: usize,
+    transforms: Transforms,
+    pub material: Material,
+    pub label: Option<String>,
+    children: Vec<Box<dyn Shape>>,
+    /// Bounds of the children, kept up to date by `push`.
+    bounds: BoundingBox,
+}
+
+impl Group {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the transform of the group, which is combined
+    /// into the parent transform of each of its children.
+    pub fn set_transform(mut self, transform: Mat4) -> Self {
+        self.transforms.set_transform(transform);
+        self.update_children();
+
+        self
+    }
+
+    /// Adds `shape` to the group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let mut group = Group::new();
+    /// group.push(Sphere::new());
+    /// assert_eq!(group.children().len(), 1);
+    /// ```
+    pub fn push<T: Shape + 'static>(&mut self, mut shape: T) {
+        shape.set_parent_transform(self.world_transform());
+        self.bounds.add_box(shape.parent_space_bounds());
+        self.children.push(Box::new(shape));
+    }
+
+    pub fn children(&self) -> &[Box<dyn Shape>] {
+        &self.children
+    }
+
+    /// Transform from group space to world space,
+    /// through every group containing this one.
+    fn world_transform(&self) -> Mat4 {
+        self.transforms.world()
+    }
+
+    fn update_children(&mut self) {
+        let world_transform = self.world_transform();
+        for child in &mut self.children {
+            child.set_parent_transform(world_transform);
+        }
+    }
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Self {
+            id: OBJECT_COUNTER.fetch_add(1, Ordering::Relaxed),
+            transforms: Transforms::default(),
+            material: Material::new(),
+            label: None,
+            children: Vec::new(),
+            bounds: BoundingBox::empty(),
+        }
+    }
+}
+
+impl Shape for Group {
+    fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection<'_>> {
+        let mut intersections = Vec::new();
+        self.local_intersect_into(local_ray, &mut intersections);
+        intersections
+    }
+
+    /// Children are skipped altogether when the ray
+    /// misses the bounding box of the group.
+    fn local_intersect_into<'s>(
+        &'s self,
+        local_ray: Ray,
+        intersections: &mut Vec<Intersection<'s>>,
+    ) {
+        if !self.bounds.intersects(&local_ray) {
+            return;
+        }
+        let start = intersections.len();
+        for child in &self.children {
+            child.intersect_into(local_ray, intersections);
+        }
+        intersections[start..].sort_by(|a, b| a.t.total_cmp(&b.t));
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.id == other.id() || self.children.iter().any(|child| child.includes(other))
+    }
+
+    /// Groups have no surface of their own, normals are
+    /// always computed on the child that was hit.
+    fn local_normal_at(&self, _local_point: Point) -> Vector {
+        panic!("local_normal_at called on a group");
+    }
+
+    /// Encloses the bounds of every child, in group space.
+    fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    /// Nested groups pass the change down to their own children.
+    fn set_parent_transform(&mut self, transform: Mat4) {
+        self.transforms.set_parent(transform);
+        self.update_children();
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn label_mut(&mut self) -> Option<&mut Option<String>> {
+        Some(&mut self.label)
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    fn debug(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cylinder::Cylinder;
+    use crate::prelude::is_equal;
+    use crate::sphere::Sphere;
+    use crate::{point, vector};
+    use std::f64::consts::FRAC_PI_2;
+    use std::sync::atomic::AtomicUsize;
+
+    const SQRT_3: f64 = 1.7320508075688772;
+
+    #[test]
+    fn test_local_intersect() {
+        // Intersecting a ray with an empty group
+        let group = Group::new();
+        let ray = Ray {
+            origin: point![0, 0, 0],
+            direction: vector![0, 0, 1],
+        };
+        assert!(group.local_intersect(ray).is_empty());
+
+        // Intersecting a ray with a nonempty group
+        let mut group = Group::new();
+        let s1 = Sphere::new();
+        let s2 = Sphere::new().set_transform(Mat4::identity().translate(0.0, 0.0, -3.0));
+        let s3 = Sphere::new().set_transform(Mat4::identity().translate(5.0, 0.0, 0.0));
+        let (id1, id2) = (s1.id, s2.id);
+        group.push(s1);
+        group.push(s2);
+        group.push(s3);
+        let ray = Ray {
+            origin: point![0, 0, -5],
+            direction: vector![0, 0, 1],
+        };
+        let intersections = group.local_intersect(ray);
+        let ids: Vec<usize> = intersections.iter().map(|i| i.object.id()).collect();
+        assert_eq!(ids, vec![id2, id2, id1, id1]);
+
+        // Intersecting into a buffer appends the sorted intersections
+        let mut buffer = vec![Intersection::new(10.0, &group)];
+        group.local_intersect_into(ray, &mut buffer);
+        let ts: Vec<f64> = buffer.iter().map(|i| i.t).collect();
+        let expected: Vec<f64> = intersections.iter().map(|i| i.t).collect();
+        assert_eq!(ts[0], 10.0);
+        assert_eq!(ts[1..], expected[..]);
+
+        // Intersecting a transformed group
+        let mut group = Group::new().set_transform(Mat4::identity().uniform_scale(2.0));
+        group.push(Sphere::new().set_transform(Mat4::identity().translate(5.0, 0.0, 0.0)));
+        let ray = Ray {
+            origin: point![10, 0, -10],
+            direction: vector![0, 0, 1],
+        };
+        assert_eq!(group.intersect(ray).len(), 2);
+    }
+
+    #[test]
+    fn test_normal_at() {
+        // Finding the normal on a child of a transformed group
+        let mut group = Group::new().set_transform(Mat4::identity().rotate_y(FRAC_PI_2));
+        group.push(Sphere::new().set_transform(Mat4::identity().translate(5.0, 0.0, 0.0)));
+        let child = &group.children()[0];
+        assert_eq!(child.normal_at(point![0, 0, -6]), vector![0, 0, -1]);
+
+        // Converting a point from world to object space
+        let mut group = Group::new().set_transform(Mat4::identity().uniform_scale(2.0));
+        group.push(Sphere::new().set_transform(Mat4::identity().translate(5.0, 0.0, 0.0)));
+        let child = &group.children()[0];
+        assert_eq!(child.world_to_object(point![10, 0, 0]), point![0, 0, 0]);
+
+        // Converting a point from world to object space through nested groups
+        let mut g2 = Group::new().set_transform(Mat4::identity().uniform_scale(2.0));
+        g2.push(Sphere::new().set_transform(Mat4::identity().translate(5.0, 0.0, 0.0)));
+        let mut g1 = Group::new().set_transform(Mat4::identity().rotate_y(FRAC_PI_2));
+        g1.push(g2);
+        let g2 = g1.children()[0].as_ref();
+        let ray = Ray {
+            origin: point![-20, 0, -10],
+            direction: vector![1, 0, 0],
+        };
+        let intersections = g1.intersect(ray);
+        assert_eq!(intersections.len(), 2);
+        let sphere = intersections[0].object;
+        assert_ne!(sphere.id(), g2.id());
+        assert_eq!(sphere.world_to_object(point![-2, 0, -10]), point![0, 0, -1]);
+
+        // Finding the normal on a child object in nested groups
+        let mut g2 = Group::new().set_transform(Mat4::identity().scale(1.0, 2.0, 3.0));
+        g2.push(Sphere::new().set_transform(Mat4::identity().translate(5.0, 0.0, 0.0)));
+        let mut g1 = Group::new().set_transform(Mat4::identity().rotate_y(FRAC_PI_2));
+        g1.push(g2);
+        let ray = Ray {
+            origin: point![1.7321, 1.1547, 0],
+            direction: vector![0, 0, -1],
+        };
+        let intersections = g1.intersect(ray);
+        let sphere = intersections[0].object;
+        let normal = sphere.normal_to_world(vector![SQRT_3 / 3.0, SQRT_3 / 3.0, SQRT_3 / 3.0]);
+        assert_eq!(normal, vector![0.28571, 0.42857, -0.85714]);
+        assert!(is_equal(normal.magnitude(), 1.0));
+        let normal = sphere.normal_at(point![1.7321, 1.1547, -5.5774]);
+        assert_eq!(normal, vector![0.28570, 0.42854, -0.85716]);
+    }
+
+    #[test]
+    fn test_bounds() {
+        // A group has a bounding box that contains its children
+        let mut group = Group::new();
+        group.push(
+            Sphere::new().set_transform(
+                Mat4::identity()
+                    .uniform_scale(2.0)
+                    .translate(2.0, 5.0, -3.0),
+            ),
+        );
+        let mut cylinder = Cylinder::new().set_transform(
+            Mat4::identity()
+                .scale(0.5, 1.0, 0.5)
+                .translate(-4.0, -1.0, 4.0),
+        );
+        cylinder.minimum = -2.0;
+        cylinder.maximum = 2.0;
+        group.push(cylinder);
+        let bounds = group.bounds();
+        assert_eq!(bounds.min, point![-4.5, -3, -5]);
+        assert_eq!(bounds.max, point![4, 7, 4.5]);
+    }
+
+    static LOCAL_INTERSECT_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    /// A sphere counting the calls to its `local_intersect`.
+    #[derive(Debug, Clone)]
+    struct CountingSphere(Sphere);
+
+    impl Shape for CountingSphere {
+        fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection<'_>> {
+            LOCAL_INTERSECT_CALLS.fetch_add(1, Ordering::Relaxed);
+            self.0.local_intersect(local_ray)
+        }
+
+        fn local_normal_at(&self, local_point: Point) -> Vector {
+            self.0.local_normal_at(local_point)
+        }
+
+        fn bounds(&self) -> BoundingBox {
+            self.0.bounds()
+        }
+
+        fn transforms(&self) -> &Transforms {
+            self.0.transforms()
+        }
+
+        fn set_parent_transform(&mut self, transform: Mat4) {
+            self.0.set_parent_transform(transform);
+        }
+
+        fn material(&self) -> &Material {
+            self.0.material()
+        }
+
+        fn material_mut(&mut self) -> &mut Material {
+            self.0.material_mut()
+        }
+
+        fn clone_box(&self) -> Box<dyn Shape> {
+            Box::new(self.clone())
+        }
+
+        fn debug(&self) -> String {
+            format!("{:?}", self)
+        }
+
+        fn id(&self) -> usize {
+            self.0.id()
+        }
+    }
+
+    #[test]
+    fn test_bounds_rejection() {
+        // A ray missing the bounds of a group never reaches its children
+        let mut group = Group::new();
+        for i in 0..500 {
+            let x = 10.0 + (i % 10) as f64 * 3.0;
+            let z = (i / 10) as f64 * 3.0;
+            group.push(CountingSphere(
+                Sphere::new().set_transform(Mat4::identity().translate(x, 0.0, z)),
+            ));
+        }
+        let ray = Ray {
+            origin: point![0, 0, 0],
+            direction: vector![-1, 0, 0],
+        };
+        assert!(group.intersect(ray).is_empty());
+        assert_eq!(LOCAL_INTERSECT_CALLS.load(Ordering::Relaxed), 0);
+
+        // A ray crossing the bounds still tests the children
+        let ray = Ray {
+            origin: point![0, 0, 0],
+            direction: vector![1, 0, 0],
+        };
+        assert_eq!(group.intersect(ray).len(), 20);
+        assert_eq!(LOCAL_INTERSECT_CALLS.load(Ordering::Relaxed), 500);
+    }
+}
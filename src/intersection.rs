@@ -1,6 +1,6 @@
 use crate::point::Point;
-use crate::prelude::is_equal;
 use crate::prelude::EPSILON;
+use crate::prelude::{epsilon_for, is_equal};
 use crate::ray::Ray;
 use crate::shape::Shape;
 use crate::vector::Vector;
@@ -9,6 +9,10 @@ use std::cmp::Ordering;
 pub struct Intersection<'a> {
     pub t: f64,
     pub object: &'a dyn Shape,
+    /// Location of the intersection relative to the edges of a triangle,
+    /// set only by shapes that need it, like `SmoothTriangle`.
+    pub u: Option<f64>,
+    pub v: Option<f64>,
 }
 
 use std::fmt;
@@ -38,30 +42,74 @@ impl<'a> PartialOrd for Intersection<'a> {
 }
 
 impl<'a> Intersection<'a> {
+    pub fn new(t: f64, object: &'a dyn Shape) -> Self {
+        Self {
+            t,
+            object,
+            u: None,
+            v: None,
+        }
+    }
+
+    pub fn with_uv(t: f64, object: &'a dyn Shape, u: f64, v: f64) -> Self {
+        Self {
+            t,
+            object,
+            u: Some(u),
+            v: Some(v),
+        }
+    }
+
+    /// Returns the intersection with the lowest nonnegative `t`,
+    /// whether or not `intersections` are sorted.
     pub fn hit(intersections: &'a [Intersection]) -> Option<&'a Intersection<'a>> {
-        match intersections
+        Self::hit_where(intersections, |_| true)
+    }
+
+    /// Same as `hit`, skipping objects whose material doesn't
+    /// cast shadows, to find what blocks a shadow ray.
+    pub fn hit_shadow(intersections: &'a [Intersection]) -> Option<&'a Intersection<'a>> {
+        Self::hit_where(intersections, |intersection| {
+            intersection.object.material().casts_shadow
+        })
+    }
+
+    /// Returns the intersection with the lowest nonnegative `t`
+    /// among those matching `predicate`.
+    fn hit_where<P>(intersections: &'a [Intersection], predicate: P) -> Option<&'a Intersection<'a>>
+    where
+        P: Fn(&Intersection) -> bool,
+    {
+        intersections
             .iter()
-            .find(|&intersection| intersection.t > 0.0 || is_equal(intersection.t, 0.0))
-        {
-            Some(intersection) => Some(intersection),
-            None => None,
-        }
+            .filter(|&intersection| intersection.t > 0.0 || is_equal(intersection.t, 0.0))
+            .filter(|&intersection| predicate(intersection))
+            .min_by(|a, b| a.t.total_cmp(&b.t))
     }
 
     /// Prepares the state of an intersection
     /// to reuse in different calculations.
+    ///
+    /// The hit is considered inside the object only when the eye vector
+    /// points away from the normal by more than `EPSILON`; a grazing eye
+    /// vector, nearly perpendicular to the normal, is treated as outside.
+    ///
+    /// `over_point` is moved off the surface by `epsilon_for` the largest
+    /// coordinate of the point, so shadow acne stays away from scenes of
+    /// any size.
     pub fn prepare(&self, ray: Ray) -> Computation<'a> {
         let t = self.t;
         let object = self.object;
         let point = ray.position(t);
         let eyev = -ray.direction;
-        let mut normal = self.object.normal_at(point);
+        let mut normal = self.object.normal_at_hit(point, self);
         let mut inside = false;
-        if normal.dot(eyev) < 0.0 {
+        if normal.dot(eyev) < -EPSILON {
             inside = true;
             normal = -normal;
         }
-        let over_point = point + normal * EPSILON;
+        let magnitude = point.x.abs().max(point.y.abs()).max(point.z.abs());
+        let over_point = point + normal * epsilon_for(magnitude);
         Computation {
             t,
             object,
@@ -97,56 +145,32 @@ mod tests {
         // The hit, when all intersections have positive `t`
         let sphere = Sphere::new();
         let mut intersections = vec![
-            Intersection {
-                t: 1.0,
-                object: &sphere,
-            },
-            Intersection {
-                t: 2.0,
-                object: &sphere,
-            },
+            Intersection::new(1.0, &sphere),
+            Intersection::new(2.0, &sphere),
         ];
         intersections.sort_by(|a, b| a.partial_cmp(b).unwrap());
         assert_eq!(
             Intersection::hit(&intersections),
-            Some(&Intersection {
-                t: 1.0,
-                object: &sphere,
-            })
+            Some(&Intersection::new(1.0, &sphere))
         );
 
         // The hit, when some intersections have negative `t`
         let sphere = Sphere::new();
         let mut intersections = vec![
-            Intersection {
-                t: -1.0,
-                object: &sphere,
-            },
-            Intersection {
-                t: 1.0,
-                object: &sphere,
-            },
+            Intersection::new(-1.0, &sphere),
+            Intersection::new(1.0, &sphere),
         ];
         intersections.sort_by(|a, b| a.partial_cmp(b).unwrap());
         assert_eq!(
             Intersection::hit(&intersections),
-            Some(&Intersection {
-                t: 1.0,
-                object: &sphere,
-            })
+            Some(&Intersection::new(1.0, &sphere))
         );
 
         // The hit, when all intersections have negative `t`
         let sphere = Sphere::new();
         let mut intersections = vec![
-            Intersection {
-                t: -2.0,
-                object: &sphere,
-            },
-            Intersection {
-                t: -1.0,
-                object: &sphere,
-            },
+            Intersection::new(-2.0, &sphere),
+            Intersection::new(-1.0, &sphere),
         ];
         intersections.sort_by(|a, b| a.partial_cmp(b).unwrap());
         assert_eq!(Intersection::hit(&intersections), None);
@@ -154,31 +178,54 @@ mod tests {
         // The hit is always the lowest nonnegative intersection
         let sphere = Sphere::new();
         let mut intersections = vec![
-            Intersection {
-                t: 5.0,
-                object: &sphere,
-            },
-            Intersection {
-                t: 7.0,
-                object: &sphere,
-            },
-            Intersection {
-                t: -3.0,
-                object: &sphere,
-            },
-            Intersection {
-                t: -2.0,
-                object: &sphere,
-            },
+            Intersection::new(5.0, &sphere),
+            Intersection::new(7.0, &sphere),
+            Intersection::new(-3.0, &sphere),
+            Intersection::new(-2.0, &sphere),
         ];
         intersections.sort_by(|a, b| a.partial_cmp(b).unwrap());
         assert_eq!(
             Intersection::hit(&intersections),
-            Some(&Intersection {
-                t: 5.0,
-                object: &sphere,
-            })
+            Some(&Intersection::new(5.0, &sphere))
         );
+
+        // The hit, when intersections are not sorted
+        let sphere = Sphere::new();
+        let intersections = vec![
+            Intersection::new(7.0, &sphere),
+            Intersection::new(-3.0, &sphere),
+            Intersection::new(5.0, &sphere),
+            Intersection::new(-2.0, &sphere),
+            Intersection::new(6.0, &sphere),
+        ];
+        assert_eq!(
+            Intersection::hit(&intersections),
+            Some(&Intersection::new(5.0, &sphere))
+        );
+    }
+
+    #[test]
+    fn test_hit_shadow() {
+        // Glass in front of a diffuse sphere lets the shadow ray through
+        let mut glass = Sphere::new();
+        glass.material.casts_shadow = false;
+        let diffuse = Sphere::new().set_transform(Mat4::identity().translate(0, 0, 0.5));
+        let ray = Ray {
+            origin: point![0, 0, -5],
+            direction: vector![0, 0, 1],
+        };
+        let mut intersections = glass.intersect(ray);
+        intersections.extend(diffuse.intersect(ray));
+        let hit = Intersection::hit(&intersections).unwrap();
+        assert_eq!(hit.object.id(), glass.id);
+        let hit = Intersection::hit_shadow(&intersections).unwrap();
+        assert_eq!(hit.object.id(), diffuse.id);
+        assert!(is_equal(hit.t, 4.5));
+
+        // Nothing casts a shadow when every object is glass
+        let intersections = glass.intersect(ray);
+        assert!(Intersection::hit(&intersections).is_some());
+        assert_eq!(Intersection::hit_shadow(&intersections), None);
     }
 
     #[test]
@@ -196,10 +243,7 @@ mod tests {
             },
         };
         let shape = Sphere::new();
-        let intersection = Intersection {
-            t: 4.0,
-            object: &shape,
-        };
+        let intersection = Intersection::new(4.0, &shape);
         let comps = intersection.prepare(ray);
         assert!(is_equal(comps.t, intersection.t));
         assert_eq!(comps.point, point![0, 0, -1]);
@@ -221,12 +265,9 @@ mod tests {
         };
 
         let shape = Sphere::new();
-        let intersection = Intersection {
-            t: 4.0,
-            object: &shape,
-        };
+        let intersection = Intersection::new(4.0, &shape);
         let comps = intersection.prepare(ray);
-        assert_eq!(comps.inside, false);
+        assert!(!comps.inside);
 
         // The hit, when an intersection occurs on the inside
         let ray = Ray {
@@ -242,14 +283,11 @@ mod tests {
             },
         };
         let shape = Sphere::new();
-        let intersection = Intersection {
-            t: 1.0,
-            object: &shape,
-        };
+        let intersection = Intersection::new(1.0, &shape);
         let comps = intersection.prepare(ray);
         assert_eq!(comps.point, point![0, 0, 1]);
         assert_eq!(comps.eyev, vector![0, 0, -1]);
-        assert_eq!(comps.inside, true);
+        assert!(comps.inside);
         // normal would have been (0, 0, 1), but is inverted!
         assert_eq!(comps.normal, vector![0, 0, -1]);
 
@@ -259,13 +297,34 @@ mod tests {
             direction: vector![0, 0, 1],
         };
         let mut shape = Sphere::new();
-        shape.transform = Mat4::identity().translate(0, 0, 1);
-        let intersection = Intersection {
-            t: 5.0,
-            object: &shape,
-        };
+        shape = shape.set_transform(Mat4::identity().translate(0, 0, 1));
+        let intersection = Intersection::new(5.0, &shape);
         let comps = intersection.prepare(ray);
         assert!(comps.over_point.z < -EPSILON / 2.0);
         assert!(comps.point.z > comps.over_point.z);
+
+        // The offset grows with the scale of the scene
+        let ray = Ray {
+            origin: point![0, 0, -5000],
+            direction: vector![0, 0, 1],
+        };
+        let shape = Sphere::new().set_transform(Mat4::identity().uniform_scale(1000));
+        let intersection = Intersection::new(4000.0, &shape);
+        let comps = intersection.prepare(ray);
+        assert!(comps.over_point.z < -1000.0 - EPSILON * 1000.0 / 2.0);
+        assert!(comps.over_point.z > -1000.0 - EPSILON * 1000.0 * 2.0);
+
+        // A grazing eye vector is consistently treated as outside
+        let shape = Sphere::new();
+        for direction in [vector![0, 1e-9, 1], vector![0, 0, 1], vector![0, -1e-9, 1]] {
+            let ray = Ray {
+                origin: point![0, 1, 0] - direction * 5,
+                direction,
+            };
+            let intersection = Intersection::new(5.0, &shape);
+            let comps = intersection.prepare(ray);
+            assert!(!comps.inside);
+            assert_eq!(comps.normal, vector![0, 1, 0]);
+        }
     }
 }
@@ -1,19 +1,31 @@
+pub mod bounds;
 pub mod camera;
 pub mod canvas;
 pub mod color;
+pub mod cone;
+pub mod csg;
+pub mod cube;
+pub mod cylinder;
+pub mod ground;
+pub mod group;
 pub mod intersection;
 pub mod light;
 pub mod mat2;
 pub mod mat3;
 pub mod mat4;
 pub mod material;
+pub mod matrix_nn;
+pub mod obj;
 pub mod pattern;
 pub mod plane;
 pub mod point;
 pub mod prelude;
 pub mod ray;
+pub mod scene;
 pub mod shape;
 pub mod sphere;
+pub mod stats;
+pub mod triangle;
 pub mod tuple;
 pub mod vector;
 pub mod world;
@@ -1,8 +1,446 @@
 use crate::color::Color;
 use crate::point::Point;
+use crate::vector::Vector;
+use crate::world::World;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-#[derive(Copy, Clone)]
-pub struct Light {
+/// A light source, either a single point, an area,
+/// a spotlight or a directional light.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Light {
+    Point(PointLight),
+    Area(AreaLight),
+    Spot(Spotlight),
+    Directional(DirectionalLight),
+}
+
+/// A light emitted from a single point, casting hard shadows,
+/// or slightly soft ones when sampled within a small sphere.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointLight {
+    pub position: Point,
+    pub intensity: Color,
+    /// Constant, linear and quadratic coefficients of the falloff
+    /// with distance, `None` for a light that doesn't fall off.
+    pub attenuation: Option<(f64, f64, f64)>,
+    /// Radius of the sphere around `position` the light is sampled in.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub softness: f64,
+    /// Number of positions sampled within the sphere, 0 and 1
+    /// both sample `position` alone, casting hard shadows.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shadow_samples: usize,
+}
+
+/// A rectangular light, from `corner` along `uvec` and `vvec`,
+/// divided into `usteps` by `vsteps` cells each sampled once,
+/// casting soft shadows.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AreaLight {
+    pub corner: Point,
+    pub uvec: Vector,
+    pub usteps: usize,
+    pub vvec: Vector,
+    pub vsteps: usize,
+    pub intensity: Color,
+    /// When set, each cell is sampled at a random position
+    /// instead of its center, trading banding for noise.
+    pub jitter: bool,
+}
+
+/// A light emitted from a single point in a cone around `direction`.
+///
+/// Points within `inner_angle` of the direction are fully lit,
+/// points beyond `outer_angle` are not lit at all, and the light
+/// fades smoothly in between. Angles are in radians.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spotlight {
     pub position: Point,
+    pub direction: Vector,
+    pub inner_angle: f64,
+    pub outer_angle: f64,
+    pub intensity: Color,
+}
+
+/// A light infinitely far away, like the sun, shining along
+/// `direction` everywhere, without falling off with distance.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DirectionalLight {
+    pub direction: Vector,
     pub intensity: Color,
 }
+
+impl Light {
+    /// Creates a `Light::Point` at `position`.
+    pub fn point(position: Point, intensity: Color) -> Self {
+        Self::Point(PointLight {
+            position,
+            intensity,
+            attenuation: None,
+            softness: 0.0,
+            shadow_samples: 1,
+        })
+    }
+
+    /// Creates a `Light::Point` at `position`, sampled `shadow_samples`
+    /// times within `softness` of it, softening the edges of its shadows
+    /// without the cost of an area light.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let light = Light::soft_point(point![0, 0, -10], color![1, 1, 1], 0.5, 8);
+    /// assert_eq!(light.directions(point![0, 0, 0]).len(), 8);
+    /// ```
+    pub fn soft_point(
+        position: Point,
+        intensity: Color,
+        softness: f64,
+        shadow_samples: usize,
+    ) -> Self {
+        Self::Point(PointLight {
+            position,
+            intensity,
+            attenuation: None,
+            softness,
+            shadow_samples,
+        })
+    }
+
+    /// Returns the factor dividing the light reaching a point
+    /// at `distance`, `c + l * d + q * d * d` for a point light
+    /// with attenuation, 1 otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let light = Light::Point(PointLight {
+    ///     position: point![0, 0, 0],
+    ///     intensity: color![1, 1, 1],
+    ///     attenuation: Some((1.0, 0.5, 0.25)),
+    ///     softness: 0.0,
+    ///     shadow_samples: 1,
+    /// });
+    /// assert_eq!(light.attenuation(2.0), 3.0);
+    /// assert_eq!(Light::point(point![0, 0, 0], color![1, 1, 1]).attenuation(2.0), 1.0);
+    /// ```
+    pub fn attenuation(&self, distance: f64) -> f64 {
+        match self {
+            Self::Point(PointLight {
+                attenuation: Some((constant, linear, quadratic)),
+                ..
+            }) => constant + linear * distance + quadratic * distance * distance,
+            _ => 1.0,
+        }
+    }
+
+    pub fn intensity(&self) -> Color {
+        match self {
+            Self::Point(light) => light.intensity,
+            Self::Area(light) => light.intensity,
+            Self::Spot(light) => light.intensity,
+            Self::Directional(light) => light.intensity,
+        }
+    }
+
+    /// Returns the fraction of the light emitted towards `point`,
+    /// less than 1 only for points outside the inner cone of a spotlight.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// # use std::f64::consts::PI;
+    /// let light = Light::Spot(Spotlight::new(
+    ///     point![0, 10, 0],
+    ///     vector![0, -1, 0],
+    ///     PI / 8.0,
+    ///     PI / 4.0,
+    ///     color![1, 1, 1],
+    /// ));
+    /// assert_eq!(light.cone_factor(point![0, 0, 0]), 1.0);
+    /// assert_eq!(light.cone_factor(point![10, 0, 0]), 0.0);
+    /// ```
+    pub fn cone_factor(&self, point: Point) -> f64 {
+        match self {
+            Self::Spot(light) => light.cone_factor(point),
+            _ => 1.0,
+        }
+    }
+
+    /// Returns, for each position the light is sampled from when
+    /// shading `point`, the unit vector from `point` towards it,
+    /// and its distance, infinite for directional lights.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let light = Light::point(point![0, 0, -10], color![1, 1, 1]);
+    /// assert_eq!(light.directions(point![0, 0, 0]), vec![(vector![0, 0, -1], 10.0)]);
+    ///
+    /// let light = Light::Directional(DirectionalLight {
+    ///     direction: vector![0, -1, 0],
+    ///     intensity: color![1, 1, 1],
+    /// });
+    /// assert_eq!(
+    ///     light.directions(point![5, 0, 5]),
+    ///     vec![(vector![0, 1, 0], f64::INFINITY)]
+    /// );
+    /// ```
+    pub fn directions(&self, point: Point) -> Vec<(Vector, f64)> {
+        let towards = |position: Point| {
+            let vector = position - point;
+            (vector.normalize(), vector.magnitude())
+        };
+        match self {
+            Self::Point(light) => light.positions(point).into_iter().map(towards).collect(),
+            Self::Area(light) => light.positions(point).into_iter().map(towards).collect(),
+            Self::Spot(light) => vec![towards(light.position)],
+            Self::Directional(light) => vec![(-light.direction.normalize(), f64::INFINITY)],
+        }
+    }
+
+    /// Returns the fraction of the light reaching `point`,
+    /// from 0 when fully shadowed to 1 when fully lit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// // Point lights evaluate the light intensity at a given point
+    /// let world = World::default();
+    /// let light = world.lights[0];
+    /// assert_eq!(light.intensity_at(point![0, 1.0001, 0], &world), 1.0);
+    /// assert_eq!(light.intensity_at(point![0, 0, 0], &world), 0.0);
+    /// ```
+    pub fn intensity_at(&self, point: Point, world: &World) -> f64 {
+        let directions = self.directions(point);
+        let lit = directions
+            .iter()
+            .filter(|&&(direction, distance)| {
+                !world.is_shadowed_towards(point, direction, distance)
+            })
+            .count();
+        lit as f64 / directions.len() as f64
+    }
+}
+
+impl PointLight {
+    /// Returns `position` alone for a single sample, or `shadow_samples`
+    /// random positions within `softness` of it, seeded by `point`
+    /// like the jitter of area lights.
+    fn positions(&self, point: Point) -> Vec<Point> {
+        if self.shadow_samples <= 1 {
+            return vec![self.position];
+        }
+        let mut rng = shading_rng(point);
+        (0..self.shadow_samples)
+            .map(|_| loop {
+                // rejection sampling, uniform within the unit sphere
+                let offset = Vector {
+                    x: rng.gen_range(-1.0..1.0),
+                    y: rng.gen_range(-1.0..1.0),
+                    z: rng.gen_range(-1.0..1.0),
+                };
+                if offset.dot(offset) <= 1.0 {
+                    break self.position + offset * self.softness;
+                }
+            })
+            .collect()
+    }
+}
+
+impl AreaLight {
+    /// Creates an area light with its edges `full_uvec` and `full_vvec`
+    /// split into `usteps` and `vsteps` cells, without jitter.
+    pub fn new(
+        corner: Point,
+        full_uvec: Vector,
+        usteps: usize,
+        full_vvec: Vector,
+        vsteps: usize,
+        intensity: Color,
+    ) -> Self {
+        Self {
+            corner,
+            uvec: full_uvec,
+            usteps,
+            vvec: full_vvec,
+            vsteps,
+            intensity,
+            jitter: false,
+        }
+    }
+
+    /// Returns the point at `offset`, within 0 and 1 on both axes,
+    /// of the cell `u`, `v`.
+    pub fn point_on_light(&self, u: usize, v: usize, offset: (f64, f64)) -> Point {
+        self.corner
+            + self.uvec * ((u as f64 + offset.0) / self.usteps as f64)
+            + self.vvec * ((v as f64 + offset.1) / self.vsteps as f64)
+    }
+
+    /// Returns one position in each cell. The jitter is seeded by
+    /// `point`, so a point always sees the same positions, whether
+    /// computing its shadows or its shading.
+    fn positions(&self, point: Point) -> Vec<Point> {
+        let mut rng = shading_rng(point);
+        let mut positions = Vec::with_capacity(self.usteps * self.vsteps);
+        for v in 0..self.vsteps {
+            for u in 0..self.usteps {
+                let offset = if self.jitter {
+                    (rng.gen::<f64>(), rng.gen::<f64>())
+                } else {
+                    (0.5, 0.5)
+                };
+                positions.push(self.point_on_light(u, v, offset));
+            }
+        }
+        positions
+    }
+}
+
+/// Returns a random generator seeded by the shaded `point`.
+pub(crate) fn shading_rng(point: Point) -> StdRng {
+    StdRng::seed_from_u64(
+        point.x.to_bits() ^ point.y.to_bits().rotate_left(21) ^ point.z.to_bits().rotate_left(42),
+    )
+}
+
+impl Spotlight {
+    /// Creates a spotlight at `position`, shining along `direction`.
+    pub fn new(
+        position: Point,
+        direction: Vector,
+        inner_angle: f64,
+        outer_angle: f64,
+        intensity: Color,
+    ) -> Self {
+        Self {
+            position,
+            direction: direction.normalize(),
+            inner_angle,
+            outer_angle,
+            intensity,
+        }
+    }
+
+    /// Smoothly steps from 0 at the outer angle to 1 at the inner angle,
+    /// comparing cosines to avoid computing the angle itself.
+    fn cone_factor(&self, point: Point) -> f64 {
+        let cos_angle = (point - self.position).normalize().dot(self.direction);
+        let cos_inner = self.inner_angle.cos();
+        let cos_outer = self.outer_angle.cos();
+        if cos_angle >= cos_inner {
+            return 1.0;
+        }
+        if cos_angle <= cos_outer {
+            return 0.0;
+        }
+        let x = (cos_angle - cos_outer) / (cos_inner - cos_outer);
+        x * x * (3.0 - 2.0 * x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color, point, vector};
+    use std::f64::consts::PI;
+
+    fn area_light() -> AreaLight {
+        AreaLight::new(
+            point![-0.5, -0.5, -5],
+            vector![1, 0, 0],
+            2,
+            vector![0, 1, 0],
+            2,
+            color![1, 1, 1],
+        )
+    }
+
+    #[test]
+    fn test_point_on_light() {
+        // Finding a single point on an area light
+        let light = AreaLight::new(
+            point![0, 0, 0],
+            vector![2, 0, 0],
+            4,
+            vector![0, 0, 1],
+            2,
+            color![1, 1, 1],
+        );
+        let cases = [
+            (0, 0, point![0.25, 0, 0.25]),
+            (1, 0, point![0.75, 0, 0.25]),
+            (0, 1, point![0.25, 0, 0.75]),
+            (2, 0, point![1.25, 0, 0.25]),
+            (3, 1, point![1.75, 0, 0.75]),
+        ];
+        for (u, v, result) in cases {
+            assert_eq!(light.point_on_light(u, v, (0.5, 0.5)), result);
+        }
+    }
+
+    #[test]
+    fn test_intensity_at() {
+        // The area light intensity function
+        let world = World::default();
+        let light = Light::Area(area_light());
+        let cases = [
+            (point![0, 0, 2], 0.0),
+            (point![1, -1, 2], 0.25),
+            (point![1.5, 0, 2], 0.5),
+            (point![1.25, 1.25, 3], 0.75),
+            (point![0, 0, -2], 1.0),
+        ];
+        for (point, result) in cases {
+            assert_eq!(light.intensity_at(point, &world), result);
+        }
+
+        // Jittered positions stay within their cells, and are
+        // the same every time the same point is shaded
+        let mut light = area_light();
+        light.jitter = true;
+        let positions = light.positions(point![1, 2, 3]);
+        assert_eq!(positions, light.positions(point![1, 2, 3]));
+        assert_ne!(positions, area_light().positions(point![1, 2, 3]));
+        assert!(positions[0].x >= -0.5 && positions[0].x <= 0.0);
+        assert!(positions[3].y >= 0.0 && positions[3].y <= 0.5);
+    }
+
+    #[test]
+    fn test_cone_factor() {
+        // A spotlight fades out between its inner and outer angles
+        let light = Light::Spot(Spotlight::new(
+            point![0, 0, 0],
+            vector![0, 0, 2],
+            PI / 6.0,
+            PI / 3.0,
+            color![1, 1, 1],
+        ));
+        let at_angle = |angle: f64| point![angle.sin(), 0, angle.cos()];
+        assert_eq!(light.cone_factor(at_angle(0.0)), 1.0);
+        assert_eq!(light.cone_factor(at_angle(PI / 6.0 - 0.01)), 1.0);
+        assert_eq!(light.cone_factor(at_angle(PI / 3.0 + 0.01)), 0.0);
+        assert_eq!(light.cone_factor(point![0, 0, -1]), 0.0);
+        let factors: Vec<f64> = (1..10)
+            .map(|step| light.cone_factor(at_angle(PI / 6.0 + PI / 6.0 * step as f64 / 10.0)))
+            .collect();
+        assert!(factors.iter().all(|&factor| factor > 0.0 && factor < 1.0));
+        assert!(factors.windows(2).all(|pair| pair[0] > pair[1]));
+
+        // Other lights shine the same way in every direction
+        let light = Light::point(point![0, 0, 0], color![1, 1, 1]);
+        assert_eq!(light.cone_factor(point![0, 0, -1]), 1.0);
+    }
+}
@@ -3,7 +3,7 @@ use crate::point::Point;
 use crate::prelude::is_equal;
 use crate::tuple::Tuple;
 use crate::vector::Vector;
-use std::ops::{Index, IndexMut, Mul};
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
 
 /// 4 by 4 matrix
 ///
@@ -41,9 +41,10 @@ use std::ops::{Index, IndexMut, Mul};
 ///     ]
 /// );
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mat4 {
-    elements: Vec<f64>,
+    elements: [f64; 16],
 }
 
 /// Creates a `Mat4` containing the arguments.
@@ -72,7 +73,7 @@ macro_rules! mat4 {
 impl Mat4 {
     pub fn zero() -> Self {
         Self {
-            elements: vec![0.0_f64; 16],
+            elements: [0.0_f64; 16],
         }
     }
 
@@ -104,6 +105,82 @@ impl Mat4 {
         ]
     }
 
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// # use trace::mat4;
+    /// let rows = [
+    ///     [1.0, 2.0, 3.0, 4.0],
+    ///     [5.0, 6.0, 7.0, 8.0],
+    ///     [9.0, 8.0, 7.0, 6.0],
+    ///     [5.0, 4.0, 3.0, 2.0],
+    /// ];
+    /// let mat = Mat4::from_rows(rows);
+    /// assert_eq!(
+    ///     mat,
+    ///     mat4![
+    ///         [1, 2, 3, 4]
+    ///         [5, 6, 7, 8]
+    ///         [9, 8, 7, 6]
+    ///         [5, 4, 3, 2]
+    ///     ]
+    /// );
+    /// assert_eq!(mat.row(1), [5.0, 6.0, 7.0, 8.0]);
+    /// ```
+    pub fn from_rows(rows: [[f64; 4]; 4]) -> Self {
+        Self {
+            elements: rows.concat().try_into().unwrap(),
+        }
+    }
+
+    /// Builds a matrix from its columns, as when assembling
+    /// an orientation from basis vectors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// // The columns of a rotation are the images of the axes
+    /// let (x, y, z) = (vector![0, 1, 0], vector![-1, 0, 0], vector![0, 0, 1]);
+    /// let mat = Mat4::from_columns([
+    ///     [x.x, x.y, x.z, 0.0],
+    ///     [y.x, y.y, y.z, 0.0],
+    ///     [z.x, z.y, z.z, 0.0],
+    ///     [0.0, 0.0, 0.0, 1.0],
+    /// ]);
+    /// assert_eq!(mat * vector![1, 0, 0], x);
+    /// assert_eq!(mat.col(1), [-1.0, 0.0, 0.0, 0.0]);
+    /// ```
+    pub fn from_columns(columns: [[f64; 4]; 4]) -> Self {
+        Self::from_rows(columns).transpose()
+    }
+
+    /// Returns the elements of row `row`.
+    pub fn row(&self, row: usize) -> [f64; 4] {
+        [
+            self[(row, 0)],
+            self[(row, 1)],
+            self[(row, 2)],
+            self[(row, 3)],
+        ]
+    }
+
+    /// Returns the elements of column `col`.
+    pub fn col(&self, col: usize) -> [f64; 4] {
+        [
+            self[(0, col)],
+            self[(1, col)],
+            self[(2, col)],
+            self[(3, col)],
+        ]
+    }
+
+    /// Returns `true` if any element is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.elements.iter().any(|element| element.is_nan())
+    }
+
     pub fn transpose(&self) -> Self {
         let mut mat = Mat4::zero();
         for row in 0..4 {
@@ -142,11 +219,27 @@ impl Mat4 {
         det
     }
 
+    /// Returns `true` unless the determinant is exactly zero or an
+    /// element is NaN, so matrices scaling by small but nonzero
+    /// factors are invertible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// assert!(Mat4::identity().uniform_scale(0.02).is_invertible());
+    /// assert!(!Mat4::identity().scale(1, 1, 0).is_invertible());
+    /// assert!(!Mat4::identity().translate(f64::NAN, 0, 0).is_invertible());
+    /// ```
+    pub fn is_invertible(&self) -> bool {
+        is_invertible(&self.elements, self.determinant())
+    }
+
     pub fn inverse(&self) -> Mat4 {
-        let det = self.determinant();
-        if is_equal(det, 0.0) {
+        if !self.is_invertible() {
             panic!("non-invertible matrix: determinant is 0.0");
         }
+        let det = self.determinant();
         let mut mat = Mat4::zero();
         for row in 0..4 {
             for col in 0..4 {
@@ -180,17 +273,20 @@ impl Mat4 {
     ///    Tuple::from(vector![-3, 4, 5])
     /// );
     /// ```
-    pub fn translate<T, U, V>(self, x: T, y: U, z: V) -> Self
+    pub fn translate<T, U, V>(mut self, x: T, y: U, z: V) -> Self
     where
         f64: From<T>,
         f64: From<U>,
         f64: From<V>,
     {
-        let mut transform = Mat4::identity();
-        transform[(0, 3)] = f64::from(x);
-        transform[(1, 3)] = f64::from(y);
-        transform[(2, 3)] = f64::from(z);
-        transform * self
+        let offsets = [f64::from(x), f64::from(y), f64::from(z)];
+        for col in 0..4 {
+            let w = self[(3, col)];
+            for (row, offset) in offsets.iter().enumerate() {
+                self[(row, col)] += offset * w;
+            }
+        }
+        self
     }
 
     /// # Examples
@@ -222,17 +318,40 @@ impl Mat4 {
     ///     Tuple::from(point![-2, 3, 4])
     /// );
     /// ```
-    pub fn scale<T, U, V>(self, x: T, y: U, z: V) -> Self
+    pub fn scale<T, U, V>(mut self, x: T, y: U, z: V) -> Self
     where
         f64: From<T>,
         f64: From<U>,
         f64: From<V>,
     {
-        let mut transform = Mat4::identity();
-        transform[(0, 0)] = f64::from(x);
-        transform[(1, 1)] = f64::from(y);
-        transform[(2, 2)] = f64::from(z);
-        transform * self
+        let factors = [f64::from(x), f64::from(y), f64::from(z)];
+        for (row, factor) in factors.iter().enumerate() {
+            for col in 0..4 {
+                self[(row, col)] *= factor;
+            }
+        }
+        self
+    }
+
+    /// Scales by the same factor along every axis,
+    /// the same as `scale(factor, factor, factor)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// assert_eq!(Mat4::identity().uniform_scale(2), Mat4::identity().scale(2, 2, 2));
+    /// assert_eq!(
+    ///     Mat4::identity().uniform_scale(0.5) * point![2, -4, 6],
+    ///     point![1, -2, 3]
+    /// );
+    /// ```
+    pub fn uniform_scale<T>(self, factor: T) -> Self
+    where
+        f64: From<T>,
+    {
+        let factor = f64::from(factor);
+        self.scale::<f64, f64, f64>(factor, factor, factor)
     }
 
     /// # Examples
@@ -257,12 +376,7 @@ impl Mat4 {
     /// );
     /// ```
     pub fn rotate_x(self, rad: f64) -> Self {
-        let mut transform = Mat4::identity();
-        transform[(1, 1)] = rad.cos();
-        transform[(1, 2)] = -rad.sin();
-        transform[(2, 1)] = rad.sin();
-        transform[(2, 2)] = rad.cos();
-        transform * self
+        self.rotate_rows(1, 2, rad)
     }
 
     /// # Examples
@@ -281,12 +395,7 @@ impl Mat4 {
     /// );
     /// ```
     pub fn rotate_y(self, rad: f64) -> Self {
-        let mut transform = Mat4::identity();
-        transform[(0, 0)] = rad.cos();
-        transform[(0, 2)] = rad.sin();
-        transform[(2, 0)] = -rad.sin();
-        transform[(2, 2)] = rad.cos();
-        transform * self
+        self.rotate_rows(2, 0, rad)
     }
 
     /// # Examples
@@ -305,12 +414,47 @@ impl Mat4 {
     /// );
     /// ```
     pub fn rotate_z(self, rad: f64) -> Self {
-        let mut transform = Mat4::identity();
-        transform[(0, 0)] = rad.cos();
-        transform[(0, 1)] = -rad.sin();
-        transform[(1, 0)] = rad.sin();
-        transform[(1, 1)] = rad.cos();
-        transform * self
+        self.rotate_rows(0, 1, rad)
+    }
+
+    /// Rotates by `rad` around `axis`, which is normalized first,
+    /// counterclockwise when looking down the axis toward the origin
+    /// as for `rotate_x`, `rotate_y` and `rotate_z`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// # use core::f64::consts::PI;
+    /// // A third of a turn around the diagonal cycles the axes
+    /// let rotation = Mat4::identity().rotate_axis(vector![1, 1, 1], 2.0 * PI / 3.0);
+    /// assert_eq!(rotation * point![1, 0, 0], point![0, 1, 0]);
+    /// assert_eq!(rotation * point![0, 1, 0], point![0, 0, 1]);
+    /// ```
+    pub fn rotate_axis(self, axis: Vector, rad: f64) -> Self {
+        let Vector { x, y, z } = axis.normalize();
+        let (sin, cos) = (rad.sin(), rad.cos());
+        let t = 1.0 - cos;
+        // Rodrigues' rotation formula
+        let rotation = mat4![
+            [cos + x * x * t, x * y * t - z * sin, x * z * t + y * sin, 0]
+            [y * x * t + z * sin, cos + y * y * t, y * z * t - x * sin, 0]
+            [z * x * t - y * sin, z * y * t + x * sin, cos + z * z * t, 0]
+            [0, 0, 0, 1]
+        ];
+        self.compose(&rotation)
+    }
+
+    /// Rotates in place by `rad` in the plane of axes `a` and `b`,
+    /// the same as multiplying by a rotation matrix from the left.
+    fn rotate_rows(mut self, a: usize, b: usize, rad: f64) -> Self {
+        let (sin, cos) = (rad.sin(), rad.cos());
+        for col in 0..4 {
+            let (value_a, value_b) = (self[(a, col)], self[(b, col)]);
+            self[(a, col)] = cos * value_a + -sin * value_b;
+            self[(b, col)] = sin * value_a + cos * value_b;
+        }
+        self
     }
 
     /// # Examples
@@ -378,7 +522,7 @@ impl Mat4 {
         transform[(1, 2)] = f64::from(y_z);
         transform[(2, 0)] = f64::from(z_x);
         transform[(2, 1)] = f64::from(z_y);
-        transform * self
+        self.compose(&transform)
     }
 
     /// # Examples
@@ -426,7 +570,81 @@ impl Mat4 {
             [-forward.x, -forward.y, -forward.z, 0]
             [0, 0, 0, 1]
         ];
-        orientation * Mat4::identity().translate(-from.x, -from.y, -from.z)
+        let [x, y, z] = (-from).as_array();
+        Mat4::identity().translate(x, y, z).compose(&orientation)
+    }
+
+    /// Splits an affine transform into its translation, scale
+    /// and rotation, so that scaling, then rotating, then translating
+    /// rebuilds it. The rotation part must be orthogonal, results
+    /// are meaningless for transforms with shearing, projection,
+    /// or a zero scale.
+    /// A reflection comes out as a negative x scale.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// # use core::f64::consts::PI;
+    /// let transform = Mat4::identity()
+    ///     .scale(1, 2, 3)
+    ///     .rotate_y(PI / 3.0)
+    ///     .translate(4, 5, 6);
+    /// let (translation, scale, rotation) = transform.decompose();
+    /// assert_eq!(translation, vector![4, 5, 6]);
+    /// assert_eq!(scale, vector![1, 2, 3]);
+    /// assert_eq!(rotation, Mat4::identity().rotate_y(PI / 3.0));
+    /// ```
+    pub fn decompose(&self) -> (Vector, Vector, Mat4) {
+        let translation = Vector {
+            x: self[(0, 3)],
+            y: self[(1, 3)],
+            z: self[(2, 3)],
+        };
+        // each column of the linear part is an axis of the rotation,
+        // stretched by the scale along that axis
+        let column = |col: usize| Vector {
+            x: self[(0, col)],
+            y: self[(1, col)],
+            z: self[(2, col)],
+        };
+        let axes = [column(0), column(1), column(2)];
+        let mut scale = Vector {
+            x: axes[0].magnitude(),
+            y: axes[1].magnitude(),
+            z: axes[2].magnitude(),
+        };
+        if axes[0].cross(axes[1]).dot(axes[2]) < 0.0 {
+            scale.x = -scale.x;
+        }
+        let factors = [scale.x, scale.y, scale.z];
+        let mut rotation = Mat4::identity();
+        for (col, (axis, factor)) in axes.iter().zip(factors).enumerate() {
+            rotation[(0, col)] = axis.x / factor;
+            rotation[(1, col)] = axis.y / factor;
+            rotation[(2, col)] = axis.z / factor;
+        }
+        (translation, scale, rotation)
+    }
+
+    /// Applies `other` after `self`, equal to `other * self`,
+    /// reusing the storage of `self`.
+    fn compose(mut self, other: &Mat4) -> Self {
+        for col in 0..4 {
+            let column = [
+                self[(0, col)],
+                self[(1, col)],
+                self[(2, col)],
+                self[(3, col)],
+            ];
+            for row in 0..4 {
+                self[(row, col)] = other[(row, 0)] * column[0]
+                    + other[(row, 1)] * column[1]
+                    + other[(row, 2)] * column[2]
+                    + other[(row, 3)] * column[3];
+            }
+        }
+        self
     }
 }
 
@@ -449,11 +667,18 @@ impl From<&[f64]> for Mat4 {
             panic!("incompatible size for Mat4, size is {}", elements.len());
         }
         Self {
-            elements: Vec::from(elements),
+            elements: elements.try_into().unwrap(),
         }
     }
 }
 
+/// Whether a matrix of `elements` and determinant `det` has an inverse,
+/// shared by `Mat4` and `Matrix` so both reject the same matrices: an
+/// exact zero determinant or any NaN, small determinants are fine.
+pub(crate) fn is_invertible(elements: &[f64], det: f64) -> bool {
+    det != 0.0 && !det.is_nan() && !elements.iter().any(|element| element.is_nan())
+}
+
 impl Index<(usize, usize)> for Mat4 {
     type Output = f64;
 
@@ -514,6 +739,41 @@ impl Mul for &Mat4 {
     }
 }
 
+/// Element-wise sum
+impl Add for Mat4 {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        for (element, rhs) in self.elements.iter_mut().zip(rhs.elements) {
+            *element += rhs;
+        }
+        self
+    }
+}
+
+/// Element-wise difference
+impl Sub for Mat4 {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        for (element, rhs) in self.elements.iter_mut().zip(rhs.elements) {
+            *element -= rhs;
+        }
+        self
+    }
+}
+
+impl Mul<f64> for Mat4 {
+    type Output = Self;
+
+    fn mul(mut self, scalar: f64) -> Self::Output {
+        for element in &mut self.elements {
+            *element *= scalar;
+        }
+        self
+    }
+}
+
 impl Mul<Tuple> for Mat4 {
     type Output = Tuple;
 
@@ -539,6 +799,31 @@ impl Mul<Tuple> for Mat4 {
     }
 }
 
+impl Mul<Tuple> for &Mat4 {
+    type Output = Tuple;
+
+    fn mul(self, rhs: Tuple) -> Self::Output {
+        Tuple {
+            x: self[(0, 0)] * rhs.x
+                + self[(0, 1)] * rhs.y
+                + self[(0, 2)] * rhs.z
+                + self[(0, 3)] * rhs.w,
+            y: self[(1, 0)] * rhs.x
+                + self[(1, 1)] * rhs.y
+                + self[(1, 2)] * rhs.z
+                + self[(1, 3)] * rhs.w,
+            z: self[(2, 0)] * rhs.x
+                + self[(2, 1)] * rhs.y
+                + self[(2, 2)] * rhs.z
+                + self[(2, 3)] * rhs.w,
+            w: self[(3, 0)] * rhs.x
+                + self[(3, 1)] * rhs.y
+                + self[(3, 2)] * rhs.z
+                + self[(3, 3)] * rhs.w,
+        }
+    }
+}
+
 impl Mul<Point> for Mat4 {
     type Output = Point;
 
@@ -605,6 +890,28 @@ impl Mul<Vector> for Mat4 {
     }
 }
 
+impl Mul<Vector> for &Mat4 {
+    type Output = Vector;
+
+    fn mul(self, rhs: Vector) -> Self::Output {
+        let rhs = Tuple::from(rhs);
+        Vector {
+            x: self[(0, 0)] * rhs.x
+                + self[(0, 1)] * rhs.y
+                + self[(0, 2)] * rhs.z
+                + self[(0, 3)] * rhs.w,
+            y: self[(1, 0)] * rhs.x
+                + self[(1, 1)] * rhs.y
+                + self[(1, 2)] * rhs.z
+                + self[(1, 3)] * rhs.w,
+            z: self[(2, 0)] * rhs.x
+                + self[(2, 1)] * rhs.y
+                + self[(2, 2)] * rhs.z
+                + self[(2, 3)] * rhs.w,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -627,7 +934,7 @@ mod tests {
             [7, 0, 5, 4]
             [6, -2, 0, 5]
         ];
-        assert_eq!(&mat * &mat.inverse(), Mat4::identity());
+        assert_eq!(mat * mat.inverse(), Mat4::identity());
     }
 
     #[test]
@@ -648,13 +955,79 @@ mod tests {
         assert_eq!(
             Mat4::identity()
                 .rotate_x(PI / 2.0)
-                .scale(5, 5, 5)
+                .uniform_scale(5)
                 .translate(10, 5, 7)
                 * Tuple::from(point![1, 0, 1]),
             Tuple::from(point![15, 0, 7])
         );
     }
 
+    #[test]
+    fn test_chain_transform_in_place() {
+        // Builders give exactly the same matrix as multiplying
+        // by a full transformation matrix at every step
+        let (sin, cos) = (0.7_f64.sin(), 0.7_f64.cos());
+        let translation = mat4![
+            [1, 0, 0, 10]
+            [0, 1, 0, -5]
+            [0, 0, 1, 7.5]
+            [0, 0, 0, 1]
+        ];
+        let scaling = mat4![
+            [2, 0, 0, 0]
+            [0, 0.5, 0, 0]
+            [0, 0, -3, 0]
+            [0, 0, 0, 1]
+        ];
+        let rotation_x = mat4![
+            [1, 0, 0, 0]
+            [0, cos, -sin, 0]
+            [0, sin, cos, 0]
+            [0, 0, 0, 1]
+        ];
+        let rotation_y = mat4![
+            [cos, 0, sin, 0]
+            [0, 1, 0, 0]
+            [-sin, 0, cos, 0]
+            [0, 0, 0, 1]
+        ];
+        let rotation_z = mat4![
+            [cos, -sin, 0, 0]
+            [sin, cos, 0, 0]
+            [0, 0, 1, 0]
+            [0, 0, 0, 1]
+        ];
+        let shearing = mat4![
+            [1, 1, 2, 0]
+            [3, 1, 4, 0]
+            [5, 6, 1, 0]
+            [0, 0, 0, 1]
+        ];
+        let chain = Mat4::identity()
+            .rotate_x(0.7)
+            .scale(2, 0.5, -3)
+            .rotate_y(0.7)
+            .translate(10, -5, 7.5)
+            .shear(1, 2, 3, 4, 5, 6)
+            .rotate_z(0.7)
+            .translate(10, -5, 7.5)
+            .scale(2, 0.5, -3);
+        let mut reference = Mat4::identity();
+        for step in [
+            &rotation_x,
+            &scaling,
+            &rotation_y,
+            &translation,
+            &shearing,
+            &rotation_z,
+            &translation,
+            &scaling,
+        ] {
+            reference = step * &reference;
+        }
+        assert_eq!(chain.elements, reference.elements);
+    }
+
     #[test]
     fn test_transpose() {
         assert_eq!(
@@ -677,6 +1050,28 @@ mod tests {
         assert_eq!(Mat4::identity().transpose(), Mat4::identity());
     }
 
+    #[test]
+    fn test_rows_columns() {
+        let mat = mat4![
+            [1, 2, 3, 4]
+            [5, 6, 7, 8]
+            [9, 8, 7, 6]
+            [5, 4, 3, 2]
+        ];
+        let rows = [mat.row(0), mat.row(1), mat.row(2), mat.row(3)];
+        let columns = [mat.col(0), mat.col(1), mat.col(2), mat.col(3)];
+        assert_eq!(Mat4::from_rows(rows), mat);
+        assert_eq!(Mat4::from_columns(columns), mat);
+        assert_eq!(Mat4::from_columns(rows), mat.transpose());
+        assert_eq!(mat.col(2), [3.0, 7.0, 7.0, 3.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_row_out_of_bounds() {
+        Mat4::identity().row(4);
+    }
+
     #[test]
     fn test_submatrix() {
         assert_eq!(
@@ -847,10 +1242,121 @@ mod tests {
             [7, 0, 5, 4]
             [6, -2, 0, 5]
         ];
-        let mat_c = &mat_a * &mat_b;
+        let mat_c = mat_a * mat_b;
         assert_eq!(mat_c * mat_b.inverse(), mat_a);
     }
 
+    #[test]
+    fn test_rotate_axis() {
+        // Rotating around the z axis matches rotate_z
+        let rotation = Mat4::identity().rotate_axis(vector![0, 0, 1], PI);
+        assert_eq!(rotation * point![1, 0, 0], point![-1, 0, 0]);
+        assert_eq!(rotation, Mat4::identity().rotate_z(PI));
+
+        // and around the other axes, whatever the length of the axis
+        for rad in [0.3, PI / 2.0, -2.0] {
+            assert_eq!(
+                Mat4::identity().rotate_axis(vector![3, 0, 0], rad),
+                Mat4::identity().rotate_x(rad)
+            );
+            assert_eq!(
+                Mat4::identity().rotate_axis(vector![0, 0.5, 0], rad),
+                Mat4::identity().rotate_y(rad)
+            );
+        }
+
+        // Rotations chain like the other transforms
+        let axis = vector![1, -2, 0.5];
+        assert_eq!(
+            Mat4::identity().translate(1, 2, 3).rotate_axis(axis, 0.7),
+            Mat4::identity().rotate_axis(axis, 0.7) * Mat4::identity().translate(1, 2, 3)
+        );
+
+        // Points on the axis stay in place
+        let rotation = Mat4::identity().rotate_axis(axis, 1.2);
+        assert_eq!(rotation * point![2, -4, 1], point![2, -4, 1]);
+    }
+
+    #[test]
+    fn test_decompose() {
+        // Composing the pieces back gives the original transform
+        let transforms = [
+            Mat4::identity(),
+            Mat4::identity().translate(-1, 2.5, 0),
+            Mat4::identity().uniform_scale(0.25).rotate_x(1.0),
+            Mat4::identity()
+                .scale(2, 0.5, 4)
+                .rotate_axis(vector![1, 1, -2], 2.4)
+                .translate(10, -3, 7),
+            Mat4::identity()
+                .scale(-1, 1, 1)
+                .rotate_z(PI / 5.0)
+                .translate(0, 1, 0),
+        ];
+        for transform in transforms {
+            let (translation, scale, rotation) = transform.decompose();
+            let rebuilt = Mat4::identity()
+                .scale(scale.x, scale.y, scale.z)
+                .compose(&rotation)
+                .translate(translation.x, translation.y, translation.z);
+            assert_eq!(rebuilt, transform);
+
+            // the rotation is orthogonal and proper
+            assert_eq!(rotation * rotation.transpose(), Mat4::identity());
+            assert!(is_equal(rotation.determinant(), 1.0));
+        }
+
+        // A reflection shows up as a negative scale
+        let (_, scale, _) = Mat4::identity().scale(1, -2, 3).decompose();
+        assert_eq!(scale, vector![-1, 2, 3]);
+    }
+
+    #[test]
+    fn test_add_sub() {
+        let a = mat4![
+            [1, 2, 3, 4]
+            [5, 6, 7, 8]
+            [9, 8, 7, 6]
+            [5, 4, 3, 2]
+        ];
+        let b = mat4![
+            [-2, 1, 2, 3]
+            [3, 2, 1, -1]
+            [4, 3, 6, 5]
+            [1, 2, 7, 8]
+        ];
+        assert_eq!(
+            a + b,
+            mat4![
+                [-1, 3, 5, 7]
+                [8, 8, 8, 7]
+                [13, 11, 13, 11]
+                [6, 6, 10, 10]
+            ]
+        );
+        assert_eq!(a + b - b, a);
+        assert_eq!(a - a, Mat4::zero());
+
+        // Multiplication distributes over the sum
+        let point = point![1, -2, 3];
+        let sum = (a + b) * point;
+        let separate = a * point + (b * point - Point::zero());
+        assert_eq!(sum, separate);
+    }
+
+    #[test]
+    fn test_mul_scalar() {
+        let a = mat4![
+            [1, 2, 3, 4]
+            [5, 6, 7, 8]
+            [9, 8, 7, 6]
+            [5, 4, 3, 2]
+        ];
+        assert_eq!(a * 2.0, a + a);
+        assert_eq!(a * 0.0, Mat4::zero());
+        assert_eq!(Mat4::identity() * 3.0 * point![1, 2, 3], point![3, 6, 9]);
+    }
+
     #[test]
     fn test_mul_tuple() {
         let mat = mat4![
@@ -861,6 +1367,10 @@ mod tests {
         ];
         let tuple = Tuple::from(point![1, 2, 3]);
         assert_eq!(mat * tuple, tuple![18, 24, 33, 1]);
+
+        // Multiplying through a reference, as with a cached inverse
+        let borrowed = &mat;
+        assert_eq!(borrowed * tuple, tuple![18, 24, 33, 1]);
     }
 
     #[test]
@@ -873,6 +1383,10 @@ mod tests {
         ];
         let point = point![1, 2, 3];
         assert_eq!(mat * point, point![18, 24, 33]);
+
+        // A borrowed matrix transforms points, translation included
+        let borrowed = &mat;
+        assert_eq!(borrowed * point, point![18, 24, 33]);
     }
 
     #[test]
@@ -885,5 +1399,9 @@ mod tests {
         ];
         let vector = vector![1, 2, 3];
         assert_eq!(mat * vector, vector![14, 22, 32]);
+
+        // A borrowed matrix transforms vectors, ignoring translation
+        let borrowed = &mat;
+        assert_eq!(borrowed * vector, vector![14, 22, 32]);
     }
 }
@@ -7,14 +7,20 @@ use crate::prelude::is_equal;
 use crate::shape::Shape;
 use crate::vector::Vector;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Material {
+    /// Patterns are left out when serializing.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub pattern: Option<Box<dyn Pattern>>,
     pub color: Color,
     pub ambient: f64,
     pub diffuse: f64,
     pub specular: f64,
     pub shininess: f64,
+    /// When unset, the object is still seen and lit,
+    /// but lets light through to whatever lies behind it.
+    pub casts_shadow: bool,
 }
 
 impl Material {
@@ -26,9 +32,146 @@ impl Material {
             diffuse: 0.9,
             specular: 0.9,
             shininess: 200.0,
+            casts_shadow: true,
         }
     }
 
+    /// A dull surface of color `color`, without highlights.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let matte = Material::matte(color![0.8, 0.2, 0.2]);
+    /// assert_eq!(matte.specular, 0.0);
+    /// assert_eq!(matte.shininess, 1.0);
+    /// ```
+    pub fn matte(color: Color) -> Self {
+        Self::new()
+            .with_color(color)
+            .with_specular(0.0)
+            .with_shininess(1.0)
+    }
+
+    /// A polished surface of color `color`, mostly lit
+    /// by its small and bright highlights.
+    pub fn metal(color: Color) -> Self {
+        Self::new()
+            .with_color(color)
+            .with_ambient(0.05)
+            .with_diffuse(0.3)
+            .with_specular(1.0)
+            .with_shininess(300.0)
+    }
+
+    /// A clear surface showing little more than its highlights,
+    /// and casting no shadow. Without refraction, what lies
+    /// behind it isn't seen through it.
+    pub fn glass() -> Self {
+        Self::new()
+            .with_ambient(0.0)
+            .with_diffuse(0.1)
+            .with_specular(1.0)
+            .with_shininess(300.0)
+            .with_casts_shadow(false)
+    }
+
+    /// Chainable setters for each field, so materials
+    /// can be built in a single expression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let material = Material::new()
+    ///     .with_color(color![1, 0.2, 1])
+    ///     .with_diffuse(0.7)
+    ///     .with_specular(0.3);
+    /// assert_eq!(material.color, color![1, 0.2, 1]);
+    /// assert_eq!(material.diffuse, 0.7);
+    /// assert_eq!(material.ambient, Material::new().ambient);
+    /// ```
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+
+        self
+    }
+
+    pub fn with_pattern(mut self, pattern: Box<dyn Pattern>) -> Self {
+        self.pattern = Some(pattern);
+
+        self
+    }
+
+    pub fn with_ambient(mut self, ambient: f64) -> Self {
+        self.ambient = ambient;
+
+        self
+    }
+
+    pub fn with_diffuse(mut self, diffuse: f64) -> Self {
+        self.diffuse = diffuse;
+
+        self
+    }
+
+    pub fn with_specular(mut self, specular: f64) -> Self {
+        self.specular = specular;
+
+        self
+    }
+
+    pub fn with_shininess(mut self, shininess: f64) -> Self {
+        self.shininess = shininess;
+
+        self
+    }
+
+    pub fn with_casts_shadow(mut self, casts_shadow: bool) -> Self {
+        self.casts_shadow = casts_shadow;
+
+        self
+    }
+
+    /// Ambient term under a white light, as seen in a world
+    /// with no lights at all.
+    pub fn ambient_at(&self, object: &dyn Shape, point: Point) -> Color {
+        self.color_at(object, point) * self.ambient
+    }
+
+    /// Color of the surface at `point`, from the pattern if any.
+    fn color_at(&self, object: &dyn Shape, point: Point) -> Color {
+        match &self.pattern {
+            Some(pattern) => pattern.at_object(object, point),
+            None => self.color,
+        }
+    }
+
+    /// Shades `point` as lit by `light`, `intensity` being the
+    /// fraction of the light reaching the point, as returned by
+    /// `Light::intensity_at`, 0 in full shadow and 1 in full light.
+    ///
+    /// Diffuse and specular are averaged over the directions
+    /// the light is sampled from, then scaled by `intensity`
+    /// and, for spotlights, by `Light::cone_factor`. Point lights
+    /// with attenuation are divided by `Light::attenuation`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// // Lighting with the eye between the light and the surface
+    /// let sphere = Sphere::new();
+    /// let light = Light::point(point![0, 0, -10], color![1, 1, 1]);
+    /// let eye = vector![0, 0, -1];
+    /// let normal = vector![0, 0, -1];
+    /// let lit = sphere.material.lighting(&sphere, light, Point::zero(), eye, normal, 1.0);
+    /// assert_eq!(lit, color![1.9, 1.9, 1.9]);
+    ///
+    /// // Lighting with the surface in shadow leaves only the ambient term
+    /// let shadowed = sphere.material.lighting(&sphere, light, Point::zero(), eye, normal, 0.0);
+    /// assert_eq!(shadowed, color![0.1, 0.1, 0.1]);
+    /// ```
     pub fn lighting(
         &self,
         object: &dyn Shape,
@@ -36,33 +179,50 @@ impl Material {
         point: Point,
         eye: Vector,
         normal: Vector,
-        in_shadow: bool,
+        intensity: f64,
     ) -> Color {
-        let color = match &self.pattern {
-            Some(pattern) => pattern.at_object(object, point),
-            None => self.color,
-        };
-        let effective_color = color * light.intensity;
-        let light_vector = (light.position - point).normalize();
-        let ambient = effective_color * self.ambient;
-        let light_dot_normal = light_vector.dot(normal);
-        let diffuse: Color;
-        let specular: Color;
-        if light_dot_normal < 0.0 || in_shadow {
-            diffuse = color![0, 0, 0];
-            specular = color![0, 0, 0];
-        } else {
-            diffuse = effective_color * self.diffuse * light_dot_normal;
+        self.lighting_occluded(object, light, point, eye, normal, intensity, 0.0)
+    }
+
+    /// Same as `lighting`, with the ambient term darkened by
+    /// `occlusion`, as returned by `World::ambient_occlusion_at`,
+    /// from 0 leaving it as is to 1 removing it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn lighting_occluded(
+        &self,
+        object: &dyn Shape,
+        light: Light,
+        point: Point,
+        eye: Vector,
+        normal: Vector,
+        intensity: f64,
+        occlusion: f64,
+    ) -> Color {
+        let effective_color = self.color_at(object, point) * light.intensity();
+        let ambient = effective_color * self.ambient * (1.0 - occlusion);
+        let intensity = intensity * light.cone_factor(point);
+        if intensity == 0.0 {
+            return ambient;
+        }
+        let directions = light.directions(point);
+        let mut sum = color![0, 0, 0];
+        for &(light_vector, distance) in &directions {
+            let light_dot_normal = light_vector.dot(normal);
+            if light_dot_normal < 0.0 {
+                continue;
+            }
+            let diffuse = effective_color * self.diffuse * light_dot_normal;
             let reflect_vector = (-light_vector).reflect(normal);
             let reflect_dot_eye = reflect_vector.dot(eye);
-            if reflect_dot_eye < 0.0 || is_equal(reflect_dot_eye, 0.0) {
-                specular = color![0, 0, 0];
+            let specular = if reflect_dot_eye < 0.0 || is_equal(reflect_dot_eye, 0.0) {
+                color![0, 0, 0]
             } else {
                 let factor = reflect_dot_eye.powf(self.shininess);
-                specular = light.intensity * self.specular * factor;
-            }
+                light.intensity() * self.specular * factor
+            };
+            sum += (diffuse + specular) / light.attenuation(distance);
         }
-        ambient + diffuse + specular
+        ambient + sum * (intensity / directions.len() as f64)
     }
 }
 
@@ -75,6 +235,7 @@ impl Default for Material {
             diffuse: 0.9,
             specular: 0.9,
             shininess: 200.0,
+            casts_shadow: true,
         }
     }
 }
@@ -86,16 +247,18 @@ impl PartialEq for Material {
             && is_equal(self.diffuse, rhs.diffuse)
             && is_equal(self.specular, rhs.specular)
             && is_equal(self.shininess, rhs.shininess)
+            && self.casts_shadow == rhs.casts_shadow
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::light::{AreaLight, DirectionalLight, PointLight, Spotlight};
     use crate::pattern::stripe::Stripe;
     use crate::sphere::Sphere;
     use crate::{color, point, vector};
-    use std::f64::consts::SQRT_2;
+    use std::f64::consts::{FRAC_1_SQRT_2, PI, SQRT_2};
 
     #[test]
     fn test_lighting() {
@@ -104,14 +267,11 @@ mod tests {
         let position = Point::zero();
         let eye = vector![0, 0, -1];
         let normal = vector![0, 0, -1];
-        let light = Light {
-            position: point![0, 0, -10],
-            intensity: color![1, 1, 1],
-        };
-        let in_shadow = false;
+        let light = Light::point(point![0, 0, -10], color![1, 1, 1]);
+        let intensity = 1.0;
         let object = Sphere::new();
         assert_eq!(
-            material.lighting(&object, light, position, eye, normal, in_shadow),
+            material.lighting(&object, light, position, eye, normal, intensity),
             color![1.9, 1.9, 1.9]
         );
 
@@ -120,14 +280,11 @@ mod tests {
         let position = Point::zero();
         let eye = vector![0, SQRT_2 / 2.0, -SQRT_2 / 2.0];
         let normal = vector![0, 0, -1];
-        let light = Light {
-            position: point![0, 0, -10],
-            intensity: color![1, 1, 1],
-        };
-        let in_shadow = false;
+        let light = Light::point(point![0, 0, -10], color![1, 1, 1]);
+        let intensity = 1.0;
         let object = Sphere::new();
         assert_eq!(
-            material.lighting(&object, light, position, eye, normal, in_shadow),
+            material.lighting(&object, light, position, eye, normal, intensity),
             color![1, 1, 1]
         );
 
@@ -136,14 +293,11 @@ mod tests {
         let position = Point::zero();
         let eye = vector![0, 0, -1];
         let normal = vector![0, 0, -1];
-        let light = Light {
-            position: point![0, 10, -10],
-            intensity: color![1, 1, 1],
-        };
-        let in_shadow = false;
+        let light = Light::point(point![0, 10, -10], color![1, 1, 1]);
+        let intensity = 1.0;
         let object = Sphere::new();
         assert_eq!(
-            material.lighting(&object, light, position, eye, normal, in_shadow),
+            material.lighting(&object, light, position, eye, normal, intensity),
             color![0.7364, 0.7364, 0.7364]
         );
 
@@ -152,14 +306,11 @@ mod tests {
         let position = Point::zero();
         let eye = vector![0, -SQRT_2 / 2.0, -SQRT_2 / 2.0];
         let normal = vector![0, 0, -1];
-        let light = Light {
-            position: point![0, 10, -10],
-            intensity: color![1, 1, 1],
-        };
-        let in_shadow = false;
+        let light = Light::point(point![0, 10, -10], color![1, 1, 1]);
+        let intensity = 1.0;
         let object = Sphere::new();
         assert_eq!(
-            material.lighting(&object, light, position, eye, normal, in_shadow),
+            material.lighting(&object, light, position, eye, normal, intensity),
             color![1.6364, 1.6364, 1.6364]
         );
 
@@ -168,14 +319,11 @@ mod tests {
         let position = Point::zero();
         let eye = vector![0, 0, -1];
         let normal = vector![0, 0, -1];
-        let light = Light {
-            position: point![0, 0, 10],
-            intensity: color![1, 1, 1],
-        };
-        let in_shadow = false;
+        let light = Light::point(point![0, 0, 10], color![1, 1, 1]);
+        let intensity = 1.0;
         let object = Sphere::new();
         assert_eq!(
-            material.lighting(&object, light, position, eye, normal, in_shadow),
+            material.lighting(&object, light, position, eye, normal, intensity),
             color![0.1, 0.1, 0.1]
         );
 
@@ -183,14 +331,11 @@ mod tests {
         let material = Material::new();
         let eyev = vector![0, 0, -1];
         let normalv = vector![0, 0, -1];
-        let light = Light {
-            position: point![0, 0, -10],
-            intensity: color![1, 1, 1],
-        };
-        let in_shadow = true;
+        let light = Light::point(point![0, 0, -10], color![1, 1, 1]);
+        let intensity = 0.0;
         let object = Sphere::new();
         assert_eq!(
-            material.lighting(&object, light, position, eyev, normalv, in_shadow),
+            material.lighting(&object, light, position, eyev, normalv, intensity),
             color![0.1, 0.1, 0.1]
         );
 
@@ -202,18 +347,208 @@ mod tests {
         material.specular = 0.0;
         let eyev = vector![0, 0, -1];
         let normalv = vector![0, 0, -1];
-        let light = Light {
-            position: point![0, 0, -10],
-            intensity: color![1, 1, 1],
-        };
+        let light = Light::point(point![0, 0, -10], color![1, 1, 1]);
         let object = Sphere::new();
         assert_eq!(
-            material.lighting(&object, light, point![0.9, 0, 0], eyev, normalv, false),
+            material.lighting(&object, light, point![0.9, 0, 0], eyev, normalv, 1.0),
             Color::WHITE
         );
         assert_eq!(
-            material.lighting(&object, light, point![1.1, 0, 0], eyev, normalv, false),
+            material.lighting(&object, light, point![1.1, 0, 0], eyev, normalv, 1.0),
             Color::BLACK
         );
     }
+
+    #[test]
+    fn test_with() {
+        // Chained setters give the same material as assigning the fields
+        let chained = Material::new()
+            .with_pattern(Box::new(Stripe::default()))
+            .with_color(color![0.2, 0.4, 0.6])
+            .with_ambient(0.3)
+            .with_diffuse(0.5)
+            .with_specular(0.7)
+            .with_shininess(50.0)
+            .with_casts_shadow(false);
+        let mut assigned = Material::new();
+        assigned.pattern = Some(Box::new(Stripe::default()));
+        assigned.color = color![0.2, 0.4, 0.6];
+        assigned.ambient = 0.3;
+        assigned.diffuse = 0.5;
+        assigned.specular = 0.7;
+        assigned.shininess = 50.0;
+        assigned.casts_shadow = false;
+        assert_eq!(format!("{:?}", chained), format!("{:?}", assigned));
+
+        // Fields not set keep their defaults
+        let material = Material::new().with_diffuse(0.2);
+        assert_eq!(material.diffuse, 0.2);
+        assert_eq!(material.ambient, Material::new().ambient);
+        assert_eq!(material.shininess, Material::new().shininess);
+    }
+
+    #[test]
+    fn test_presets() {
+        let red = color![1, 0, 0];
+        let matte = Material::matte(red);
+        assert_eq!(matte.color, red);
+        assert_eq!(matte.specular, 0.0);
+        assert_eq!(matte.shininess, 1.0);
+        assert_eq!(matte.diffuse, Material::new().diffuse);
+
+        let metal = Material::metal(red);
+        assert_eq!(metal.color, red);
+        assert_eq!(metal.diffuse, 0.3);
+        assert_eq!(metal.specular, 1.0);
+        assert_eq!(metal.shininess, 300.0);
+        assert!(metal.casts_shadow);
+
+        let glass = Material::glass();
+        assert_eq!(glass.ambient, 0.0);
+        assert_eq!(glass.diffuse, 0.1);
+        assert_eq!(glass.specular, 1.0);
+        assert!(!glass.casts_shadow);
+    }
+
+    #[test]
+    fn test_lighting_occluded() {
+        // Occlusion darkens the ambient term alone
+        let material = Material::new();
+        let object = Sphere::new();
+        let light = Light::point(point![0, 0, -10], color![1, 1, 1]);
+        let (point, eye, normal) = (Point::zero(), vector![0, 0, -1], vector![0, 0, -1]);
+        let lit = |intensity, occlusion| {
+            material.lighting_occluded(&object, light, point, eye, normal, intensity, occlusion)
+        };
+        assert_eq!(
+            lit(1.0, 0.0),
+            material.lighting(&object, light, point, eye, normal, 1.0)
+        );
+        assert_eq!(lit(1.0, 0.25), color![1.875, 1.875, 1.875]);
+        assert_eq!(lit(0.0, 0.25), color![0.075, 0.075, 0.075]);
+        assert_eq!(lit(0.0, 1.0), Color::BLACK);
+
+        // including the ambient term of a dimmed light
+        let light = Light::point(point![0, 0, -10], color![0.5, 0.5, 0.5]);
+        let dimmed = material.lighting_occluded(&object, light, point, eye, normal, 0.0, 0.5);
+        assert_eq!(dimmed, color![0.025, 0.025, 0.025]);
+    }
+
+    #[test]
+    fn test_lighting_intensity() {
+        // The light intensity attenuates the diffuse and specular color
+        let mut material = Material::new();
+        material.ambient = 0.1;
+        material.diffuse = 0.9;
+        material.specular = 0.0;
+        material.color = color![1, 1, 1];
+        let light = Light::point(point![0, 0, -10], color![1, 1, 1]);
+        let object = Sphere::new();
+        let point = point![0, 0, -1];
+        let eye = vector![0, 0, -1];
+        let normal = vector![0, 0, -1];
+        let cases = [
+            (1.0, color![1, 1, 1]),
+            (0.5, color![0.55, 0.55, 0.55]),
+            (0.0, color![0.1, 0.1, 0.1]),
+        ];
+        for (intensity, result) in cases {
+            assert_eq!(
+                material.lighting(&object, light, point, eye, normal, intensity),
+                result
+            );
+        }
+
+        // Lighting samples every position of an area light
+        let light = Light::Area(AreaLight::new(
+            point![-0.5, -0.5, -5],
+            vector![1, 0, 0],
+            2,
+            vector![0, 1, 0],
+            2,
+            color![1, 1, 1],
+        ));
+        let mut material = Material::new();
+        material.ambient = 0.1;
+        material.diffuse = 0.9;
+        material.specular = 0.0;
+        let cases = [
+            (point![0, 0, -1], color![0.9965, 0.9965, 0.9965]),
+            (
+                point![0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2],
+                color![0.62318, 0.62318, 0.62318],
+            ),
+        ];
+        for (point, result) in cases {
+            let normal = vector![point.x, point.y, point.z];
+            let eye = (point![0, 0, -5] - point).normalize();
+            assert_eq!(
+                material.lighting(&object, light, point, eye, normal, 1.0),
+                result
+            );
+        }
+
+        // An attenuated point light dims with distance, leaving a hotspot under it
+        let light = Light::Point(PointLight {
+            position: point![0, 1, 0],
+            intensity: color![1, 1, 1],
+            attenuation: Some((1.0, 0.0, 1.0)),
+            softness: 0.0,
+            shadow_samples: 1,
+        });
+        let eye = vector![0, 1, 0];
+        let normal = vector![0, 1, 0];
+        let cases = [
+            (point![0, 0, 0], color![0.55, 0.55, 0.55]),
+            (point![1, 0, 0], color![0.31213, 0.31213, 0.31213]),
+            (point![3, 0, 0], color![0.12587, 0.12587, 0.12587]),
+        ];
+        for (point, result) in cases {
+            assert_eq!(
+                material.lighting(&object, light, point, eye, normal, 1.0),
+                result
+            );
+        }
+
+        // A spotlight lights a pool under it, and nothing outside of its cone
+        let light = Light::Spot(Spotlight::new(
+            point![0, 10, 0],
+            vector![0, -1, 0],
+            PI / 16.0,
+            PI / 8.0,
+            color![1, 1, 1],
+        ));
+        let eye = vector![0, 1, 0];
+        let normal = vector![0, 1, 0];
+        let cases = [
+            (point![0, 0, 0], color![1, 1, 1]),
+            (point![10, 0, 0], color![0.1, 0.1, 0.1]),
+        ];
+        for (point, result) in cases {
+            assert_eq!(
+                material.lighting(&object, light, point, eye, normal, 1.0),
+                result
+            );
+        }
+
+        // A directional light shines from the same direction on every point
+        let light = Light::Directional(DirectionalLight {
+            direction: vector![0, 0, 1],
+            intensity: color![1, 1, 1],
+        });
+        let material = Material::new();
+        for point in [point![0, 0, 0], point![100, -50, 1e6]] {
+            assert_eq!(
+                material.lighting(
+                    &object,
+                    light,
+                    point,
+                    vector![0, 0, -1],
+                    vector![0, 0, -1],
+                    1.0
+                ),
+                color![1.9, 1.9, 1.9]
+            );
+        }
+    }
 }
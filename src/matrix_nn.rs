@@ -0,0 +1,325 @@
+use crate::mat4::{is_invertible, Mat4};
+use crate::prelude::is_equal;
+use std::ops::{Index, IndexMut, Mul};
+
+/// `rows` by `cols` matrix, of any size.
+///
+/// Unlike `Mat4`, which expands cofactors, `determinant` and `inverse`
+/// use Gaussian elimination with partial pivoting, in O(n^3).
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// # use trace::mat4;
+/// let mat = mat4![
+///     [-5, 2, 6, -8]
+///     [1, -5, 1, 8]
+///     [7, 7, -6, -7]
+///     [1, -3, 7, 4]
+/// ];
+/// let matrix = Matrix::from(&mat);
+/// assert!(is_equal(matrix.determinant(), mat.determinant()));
+/// assert_eq!(Mat4::from(matrix.inverse()), mat.inverse());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Matrix {
+    pub rows: usize,
+    pub cols: usize,
+    elements: Vec<f64>,
+}
+
+impl Matrix {
+    pub fn zero(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            elements: vec![0.0_f64; rows * cols],
+        }
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut mat = Self::zero(size, size);
+        for index in 0..size {
+            mat[(index, index)] = 1.0;
+        }
+        mat
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut mat = Self::zero(self.cols, self.rows);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                mat[(col, row)] = self[(row, col)];
+            }
+        }
+        mat
+    }
+
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let mut mat = Matrix::identity(5);
+    /// mat[(0, 4)] = 7.0;
+    /// mat[(2, 2)] = 3.0;
+    /// mat[(4, 0)] = 2.0;
+    /// assert!(is_equal(mat.determinant(), -39.0));
+    /// ```
+    pub fn determinant(&self) -> f64 {
+        self.assert_square();
+        let mut mat = self.clone();
+        let mut det = 1.0;
+        for col in 0..self.cols {
+            let pivot = mat.pivot_row(col);
+            if mat[(pivot, col)] == 0.0 {
+                return 0.0;
+            }
+            if pivot != col {
+                mat.swap_rows(pivot, col);
+                det = -det;
+            }
+            det *= mat[(col, col)];
+            for row in (col + 1)..self.rows {
+                let factor = mat[(row, col)] / mat[(col, col)];
+                for c in col..self.cols {
+                    mat[(row, c)] -= factor * mat[(col, c)];
+                }
+            }
+        }
+        det
+    }
+
+    /// Returns the inverse, found by Gauss-Jordan elimination.
+    pub fn inverse(&self) -> Matrix {
+        self.assert_square();
+        let size = self.rows;
+        let mut mat = self.clone();
+        let mut inverse = Self::identity(size);
+        let mut det = 1.0;
+        for col in 0..size {
+            let pivot = mat.pivot_row(col);
+            if pivot != col {
+                mat.swap_rows(pivot, col);
+                inverse.swap_rows(pivot, col);
+                det = -det;
+            }
+            let value = mat[(col, col)];
+            det *= value;
+            if value == 0.0 {
+                break;
+            }
+            for c in 0..size {
+                mat[(col, c)] /= value;
+                inverse[(col, c)] /= value;
+            }
+            for row in (0..size).filter(|&row| row != col) {
+                let factor = mat[(row, col)];
+                for c in 0..size {
+                    mat[(row, c)] -= factor * mat[(col, c)];
+                    inverse[(row, c)] -= factor * inverse[(col, c)];
+                }
+            }
+        }
+        if !is_invertible(&self.elements, det) {
+            panic!("non-invertible matrix: determinant is 0.0");
+        }
+        inverse
+    }
+
+    /// Returns the row, from `col` down, with the
+    /// largest absolute value in column `col`.
+    fn pivot_row(&self, col: usize) -> usize {
+        (col..self.rows)
+            .max_by(|&a, &b| self[(a, col)].abs().total_cmp(&self[(b, col)].abs()))
+            .unwrap()
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for col in 0..self.cols {
+            self.elements.swap(a * self.cols + col, b * self.cols + col);
+        }
+    }
+
+    fn assert_square(&self) {
+        if self.rows != self.cols {
+            panic!("non-square matrix: size is {} by {}", self.rows, self.cols);
+        }
+    }
+}
+
+impl PartialEq for Matrix {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.rows == rhs.rows
+            && self.cols == rhs.cols
+            && self
+                .elements
+                .iter()
+                .zip(&rhs.elements)
+                .all(|(&lhs, &rhs)| is_equal(lhs, rhs))
+    }
+}
+
+impl From<&Mat4> for Matrix {
+    fn from(mat: &Mat4) -> Self {
+        let mut matrix = Self::zero(4, 4);
+        for row in 0..4 {
+            for col in 0..4 {
+                matrix[(row, col)] = mat[(row, col)];
+            }
+        }
+        matrix
+    }
+}
+
+impl From<Matrix> for Mat4 {
+    fn from(matrix: Matrix) -> Self {
+        if matrix.rows != 4 || matrix.cols != 4 {
+            panic!(
+                "incompatible size for Mat4, size is {} by {}",
+                matrix.rows, matrix.cols
+            );
+        }
+        Mat4::from(&matrix.elements[..])
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = f64;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        if index.0 >= self.rows || index.1 >= self.cols {
+            panic!(
+                "index out of bounds: Matrix is {} by {}, index is [({}, {})]",
+                self.rows, self.cols, index.0, index.1
+            );
+        }
+        &self.elements[index.0 * self.cols + index.1]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        if index.0 >= self.rows || index.1 >= self.cols {
+            panic!(
+                "index out of bounds: Matrix is {} by {}, index is [({}, {})]",
+                self.rows, self.cols, index.0, index.1
+            );
+        }
+        &mut self.elements[index.0 * self.cols + index.1]
+    }
+}
+
+impl Mul for &Matrix {
+    type Output = Matrix;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.cols != rhs.rows {
+            panic!(
+                "incompatible sizes for multiplication: {} by {} times {} by {}",
+                self.rows, self.cols, rhs.rows, rhs.cols
+            );
+        }
+        let mut mat = Matrix::zero(self.rows, rhs.cols);
+        for row in 0..self.rows {
+            for col in 0..rhs.cols {
+                mat[(row, col)] = (0..self.cols)
+                    .map(|index| self[(row, index)] * rhs[(index, col)])
+                    .sum();
+            }
+        }
+        mat
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+    use crate::vector::Vector;
+    use crate::{mat4, point, vector};
+    use core::f64::consts::PI;
+
+    #[test]
+    fn test_determinant() {
+        // Determinants agree with the cofactor expansion of `Mat4`
+        let cases = [
+            mat4![
+                [-2, -8, 3, 5]
+                [-3, 1, 7, 3]
+                [1, 2, -9, 6]
+                [-6, 7, 7, -9]
+            ],
+            mat4![
+                [6, 4, 4, 4]
+                [5, 5, 7, 6]
+                [4, -9, 3, -7]
+                [9, 1, 7, -6]
+            ],
+            mat4![
+                [-4, 2, -2, -3]
+                [9, 6, 2, 6]
+                [0, -5, 1, -5]
+                [0, 0, 0, 0]
+            ],
+            Mat4::identity()
+                .rotate_x(PI / 3.0)
+                .scale(2, 3, 0.5)
+                .translate(1, -2, 3),
+        ];
+        for mat in cases {
+            assert!(is_equal(
+                Matrix::from(&mat).determinant(),
+                mat.determinant()
+            ));
+        }
+
+        // A matrix needing a row swap for its first pivot
+        let mut mat = Matrix::zero(2, 2);
+        mat[(0, 1)] = 1.0;
+        mat[(1, 0)] = 1.0;
+        assert!(is_equal(mat.determinant(), -1.0));
+    }
+
+    #[test]
+    fn test_inverse() {
+        // Inverses agree with the adjugate method of `Mat4`
+        let cases = [
+            mat4![
+                [8, -5, 9, 2]
+                [7, 5, 6, 1]
+                [-6, 0, 9, 6]
+                [-3, 0, -9, -4]
+            ],
+            mat4![
+                [9, 3, 0, 9]
+                [-5, -2, -6, -3]
+                [-4, 9, 6, 4]
+                [-7, 6, 6, 2]
+            ],
+            Mat4::identity().view_transform(point![1, 3, 2], point![4, -2, 8], vector![1, 1, 0]),
+        ];
+        for mat in cases {
+            assert_eq!(Mat4::from(Matrix::from(&mat).inverse()), mat.inverse());
+        }
+
+        // Multiplying a matrix by its inverse gives the identity
+        let mut mat = Matrix::identity(6);
+        for row in 0..6 {
+            for col in 0..6 {
+                mat[(row, col)] += ((row * 7 + col * 3) % 5) as f64 - 2.0;
+            }
+        }
+        assert_eq!(&mat * &mat.inverse(), Matrix::identity(6));
+
+        // Small but nonzero determinants are invertible through both
+        let mat = Mat4::identity().uniform_scale(0.02).translate(1, -2, 3);
+        assert_eq!(Mat4::from(Matrix::from(&mat).inverse()), mat.inverse());
+    }
+
+    #[test]
+    #[should_panic(expected = "non-invertible matrix")]
+    fn test_inverse_singular() {
+        Matrix::zero(3, 3).inverse();
+    }
+}
@@ -0,0 +1,309 @@
+use crate::group::Group;
+use crate::point::Point;
+use crate::triangle::{SmoothTriangle, Triangle};
+use crate::vector::Vector;
+
+/// A triangle of indices into the `vertices`, and optionally
+/// the `normals`, of an `ObjData`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Face {
+    pub vertices: [usize; 3],
+    pub normals: Option<[usize; 3]>,
+}
+
+/// Geometry read from a Wavefront OBJ file by `parse_obj`.
+/// Faces are split into triangles of indices into `vertices` and `normals`.
+#[derive(Debug, Default, PartialEq)]
+pub struct ObjData {
+    pub vertices: Vec<Point>,
+    pub normals: Vec<Vector>,
+    /// Triangles defined before any `g` line.
+    pub triangles: Vec<Face>,
+    /// Triangles of each named group, in order of first appearance.
+    pub groups: Vec<(String, Vec<Face>)>,
+    /// Number of lines that were not understood, and skipped.
+    pub ignored: usize,
+}
+
+/// Parses the vertices, normals, faces and groups of an OBJ file.
+///
+/// Polygons are split into triangles fanning out from their first vertex.
+/// Face entries may be `v`, `v/vt`, `v/vt/vn` or `v//vn`, texture
+/// indices are ignored. Indices start at 1, negative indices count back
+/// from the last vertex or normal read so far. Any line that can't be
+/// parsed is counted in `ignored`.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// # use trace::obj::parse_obj;
+/// let obj = parse_obj("v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 3 4\n");
+/// assert_eq!(obj.vertices.len(), 4);
+/// assert_eq!(obj.triangles.len(), 2);
+/// assert_eq!(obj.triangles[1].vertices, [0, 2, 3]);
+/// ```
+pub fn parse_obj(source: &str) -> ObjData {
+    let mut obj = ObjData::default();
+    let mut group: Option<usize> = None;
+    for line in source.lines() {
+        let mut words = line.split_whitespace();
+        let parsed = match words.next() {
+            Some("v") => parse_vertex(words).map(|vertex| obj.vertices.push(vertex)),
+            Some("vn") => parse_vertex(words).map(|normal| {
+                obj.normals.push(Vector {
+                    x: normal.x,
+                    y: normal.y,
+                    z: normal.z,
+                })
+            }),
+            Some("f") => parse_face(words, obj.vertices.len(), obj.normals.len()).map(|face| {
+                let triangles = match group {
+                    Some(index) => &mut obj.groups[index].1,
+                    None => &mut obj.triangles,
+                };
+                // normals are only used when given for every vertex
+                let smooth = face.iter().all(|(_, normal)| normal.is_some());
+                for i in 1..(face.len() - 1) {
+                    let [a, b, c] = [face[0], face[i], face[i + 1]];
+                    triangles.push(Face {
+                        vertices: [a.0, b.0, c.0],
+                        normals: smooth.then(|| [a.1.unwrap(), b.1.unwrap(), c.1.unwrap()]),
+                    });
+                }
+            }),
+            Some("g") => words.next().map(|name| {
+                let index = obj.groups.iter().position(|(group, _)| group == name);
+                group = Some(index.unwrap_or_else(|| {
+                    obj.groups.push((name.to_string(), Vec::new()));
+                    obj.groups.len() - 1
+                }));
+            }),
+            // blank lines and comments are not worth counting
+            None => Some(()),
+            Some(word) if word.starts_with('#') => Some(()),
+            Some(_) => None,
+        };
+        if parsed.is_none() {
+            obj.ignored += 1;
+        }
+    }
+    obj
+}
+
+fn parse_vertex<'a>(mut words: impl Iterator<Item = &'a str>) -> Option<Point> {
+    let mut coordinate = || words.next()?.parse::<f64>().ok();
+    Some(Point {
+        x: coordinate()?,
+        y: coordinate()?,
+        z: coordinate()?,
+    })
+}
+
+/// Returns the 0-based vertex and normal indices of a face, given
+/// the number of vertices and normals read so far, or `None` if any
+/// of them is invalid.
+fn parse_face<'a>(
+    words: impl Iterator<Item = &'a str>,
+    vertex_count: usize,
+    normal_count: usize,
+) -> Option<Vec<(usize, Option<usize>)>> {
+    let mut face = Vec::new();
+    for word in words {
+        let mut indices = word.split('/');
+        let vertex = parse_index(indices.next()?, vertex_count)?;
+        // the texture index, possibly empty, is skipped
+        let normal = match indices.nth(1) {
+            Some(index) => Some(parse_index(index, normal_count)?),
+            None => None,
+        };
+        face.push((vertex, normal));
+    }
+    if face.len() < 3 {
+        return None;
+    }
+    Some(face)
+}
+
+/// Converts a 1-based or negative index into a 0-based one,
+/// given the number of elements read so far.
+fn parse_index(word: &str, count: usize) -> Option<usize> {
+    let index: isize = word.parse().ok()?;
+    let index = if index < 0 {
+        count as isize + index
+    } else {
+        index - 1
+    };
+    if index < 0 || index as usize >= count {
+        return None;
+    }
+    Some(index as usize)
+}
+
+impl ObjData {
+    /// Converts the parsed geometry into a `Group` of triangles,
+    /// each named group becoming a sub-group. Faces with normals
+    /// become `SmoothTriangle`s, the others flat `Triangle`s.
+    pub fn to_group(self) -> Group {
+        let mut group = Group::new();
+        for face in &self.triangles {
+            self.push_face(&mut group, face);
+        }
+        for (_, faces) in &self.groups {
+            let mut sub_group = Group::new();
+            for face in faces {
+                self.push_face(&mut sub_group, face);
+            }
+            group.push(sub_group);
+        }
+        group
+    }
+
+    fn push_face(&self, group: &mut Group, face: &Face) {
+        let [p1, p2, p3] = face.vertices.map(|index| self.vertices[index]);
+        match face.normals {
+            Some(normals) => {
+                let [n1, n2, n3] = normals.map(|index| self.normals[index]);
+                group.push(SmoothTriangle::new(p1, p2, p3, n1, n2, n3));
+            }
+            None => group.push(Triangle::new(p1, p2, p3)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{point, vector};
+
+    fn vertices(faces: &[Face]) -> Vec<[usize; 3]> {
+        faces.iter().map(|face| face.vertices).collect()
+    }
+
+    #[test]
+    fn test_parse_obj() {
+        // Ignoring unrecognized lines
+        let obj = parse_obj(
+            "There was a young lady named Bright\n\
+             who traveled much faster than light.\n\
+             \n\
+             She set out one day\n",
+        );
+        assert_eq!(obj.ignored, 3);
+        assert!(obj.vertices.is_empty());
+
+        // Vertex records
+        let obj = parse_obj("v -1 1 0\nv -1.0000 0.5000 0.0000\nv 1 0 0\nv 1 1 0\n");
+        assert_eq!(
+            obj.vertices,
+            vec![
+                point![-1, 1, 0],
+                point![-1, 0.5, 0],
+                point![1, 0, 0],
+                point![1, 1, 0]
+            ]
+        );
+
+        // Parsing triangle faces, with negative and `v/vt` indices
+        let obj = parse_obj("v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 3\nf 1/1 -2 -1\n");
+        assert_eq!(vertices(&obj.triangles), vec![[0, 1, 2], [0, 2, 3]]);
+        assert_eq!(obj.ignored, 0);
+
+        // Faces referring to missing vertices are ignored
+        let obj = parse_obj("v 0 0 0\nf 1 2 3\nf 0 1 1\n");
+        assert!(obj.triangles.is_empty());
+        assert_eq!(obj.ignored, 2);
+
+        // Triangulating polygons
+        let obj = parse_obj("v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\nv 0 2 0\nf 1 2 3 4 5\n");
+        assert_eq!(
+            vertices(&obj.triangles),
+            vec![[0, 1, 2], [0, 2, 3], [0, 3, 4]]
+        );
+
+        // Triangles in groups
+        let obj = parse_obj(
+            "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\ng FirstGroup\nf 1 2 3\ng SecondGroup\nf 1 3 4\n",
+        );
+        assert!(obj.triangles.is_empty());
+        assert_eq!(obj.groups[0].0, "FirstGroup");
+        assert_eq!(vertices(&obj.groups[0].1), vec![[0, 1, 2]]);
+        assert_eq!(obj.groups[1].0, "SecondGroup");
+        assert_eq!(vertices(&obj.groups[1].1), vec![[0, 2, 3]]);
+
+        // Vertex normal records
+        let obj = parse_obj(
+            "vn 0 0 1
+vn 0.707 0 -0.707
+vn 1 2 3
+",
+        );
+        assert_eq!(
+            obj.normals,
+            vec![
+                vector![0, 0, 1],
+                vector![0.707, 0, -0.707],
+                vector![1, 2, 3]
+            ]
+        );
+
+        // Faces with normals, in every `v/vt/vn` form
+        let obj = parse_obj(
+            "v 0 1 0
+v -1 0 0
+v 1 0 0
+             vn -1 0 0
+vn 1 0 0
+vn 0 1 0
+             f 1//3 2//1 3//2
+f 1/0/3 2/1/1 3/7/2
+f 1 2 3
+f 1//3 2 3//2
+",
+        );
+        assert_eq!(obj.ignored, 0);
+        assert_eq!(vertices(&obj.triangles), vec![[0, 1, 2]; 4]);
+        assert_eq!(obj.triangles[0].normals, Some([2, 0, 1]));
+        assert_eq!(obj.triangles[1].normals, Some([2, 0, 1]));
+        assert_eq!(obj.triangles[2].normals, None);
+        assert_eq!(obj.triangles[3].normals, None);
+    }
+
+    #[test]
+    fn test_to_group() {
+        // Converting a cube to a group of triangles
+        let obj = parse_obj(
+            "# cube\n\
+             v -1 -1 -1\nv 1 -1 -1\nv 1 1 -1\nv -1 1 -1\n\
+             v -1 -1 1\nv 1 -1 1\nv 1 1 1\nv -1 1 1\n\
+             f 1 2 3 4\nf 5 8 7 6\nf 1 5 6 2\n\
+             f 2 6 7 3\nf 3 7 8 4\nf 4 8 5 1\n",
+        );
+        assert_eq!(obj.ignored, 0);
+        assert_eq!(obj.triangles.len(), 12);
+        let group = obj.to_group();
+        assert_eq!(group.children().len(), 12);
+
+        // Converting named groups to sub-groups
+        let obj =
+            parse_obj("v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 3\ng Top\nf 1 3 4\nf 1 2 4\n");
+        let group = obj.to_group();
+        assert_eq!(group.children().len(), 2);
+
+        // Faces with normals become smooth triangles
+        let obj = parse_obj(
+            "v 0 1 0
+v -1 0 0
+v 1 0 0
+vn -1 0 0
+vn 1 0 0
+vn 0 1 0
+f 1//3 2//1 3//2
+f 1 2 3
+",
+        );
+        let group = obj.to_group();
+        assert!(group.children()[0].debug().starts_with("SmoothTriangle"));
+        assert!(group.children()[1].debug().starts_with("Triangle"));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::color::Color;
 use crate::mat4::Mat4;
 use crate::point::Point;
+use crate::prelude::is_equal;
 use crate::shape::Shape;
 use std::fmt::Debug;
 
@@ -8,32 +9,81 @@ pub mod blended;
 pub mod checkers;
 pub mod checkers_nested;
 pub mod gradient;
+pub mod image_texture;
 pub mod perturb;
 pub mod radial_gradient;
 pub mod ring;
+pub mod shells;
 pub mod solid;
 pub mod stripe;
+pub mod texture_map;
 
-pub trait Pattern {
+/// Patterns are `Send` and `Sync` so worlds can be rendered from several threads.
+pub trait Pattern: Send + Sync {
     fn at_object(&self, object: &dyn Shape, world_point: Point) -> Color {
-        let object_point = object.transform().inverse() * world_point;
-        let pattern_point = self.transform().inverse() * object_point;
+        let object_point = object.world_to_object(world_point);
+        self.at_parent(object_point)
+    }
+
+    /// Returns the color at a point in the space of the object, or of
+    /// the pattern this one is nested in, going through its transform.
+    /// Nested patterns look their children up with this, not `at`.
+    fn at_parent(&self, parent_point: Point) -> Color {
+        let pattern_point = self.transform().inverse() * parent_point;
         self.at(pattern_point)
     }
 
     fn at(&self, point: Point) -> Color;
     fn transform(&self) -> &Mat4;
 
+    /// Returns a boxed copy of the pattern, to clone materials.
+    fn clone_box(&self) -> Box<dyn Pattern>;
+
     fn debug_local(&self) -> String;
 }
 
+impl Clone for Box<dyn Pattern> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
 impl Debug for dyn Pattern {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "{:?}", self.debug_local())
     }
 }
 
-#[derive(Debug)]
+/// Width of the neighborhood antialiased patterns are averaged over.
+const SMOOTHING_WIDTH: f64 = 0.2;
+
+/// Returns the average, over `SMOOTHING_WIDTH` around `x`, of the wave
+/// that is 1 where `x.floor()` is even and -1 where it's odd.
+///
+/// Points on a boundary of the wave keep its value there, so a surface
+/// lying on a boundary, like a floor at y = 0, isn't blurred to gray.
+pub(crate) fn smooth_square_wave(x: f64) -> f64 {
+    if is_equal(x, x.round()) {
+        return if is_equal(x.floor() % 2.0, 0.0) {
+            1.0
+        } else {
+            -1.0
+        };
+    }
+    // integral of the wave from 0, a triangle wave
+    let integral = |x: f64| {
+        let t = x.rem_euclid(2.0);
+        if t < 1.0 {
+            t
+        } else {
+            2.0 - t
+        }
+    };
+    let half = SMOOTHING_WIDTH / 2.0;
+    (integral(x + half) - integral(x - half)) / SMOOTHING_WIDTH
+}
+
+#[derive(Debug, Clone)]
 pub struct TestPattern {
     pub transform: Mat4,
 }
@@ -48,6 +98,10 @@ impl Pattern for TestPattern {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
@@ -63,7 +117,7 @@ mod tests {
     fn test_at_object() {
         // A pattern with an object transformation
         let mut shape = Sphere::new();
-        shape.transform = Mat4::identity().scale(2, 2, 2);
+        shape = shape.set_transform(Mat4::identity().uniform_scale(2));
         let pattern = TestPattern {
             transform: Mat4::identity(),
         };
@@ -78,7 +132,7 @@ mod tests {
         let mut pattern = TestPattern {
             transform: Mat4::identity(),
         };
-        pattern.transform = Mat4::identity().scale(2, 2, 2);
+        pattern.transform = Mat4::identity().uniform_scale(2);
         assert_eq!(
             pattern.at_object(&shape, point![2, 3, 4]),
             color![1, 1.5, 2]
@@ -86,7 +140,7 @@ mod tests {
 
         // A pattern with both an object and a pattern transformation
         let mut shape = Sphere::new();
-        shape.transform = Mat4::identity().scale(2, 2, 2);
+        shape = shape.set_transform(Mat4::identity().uniform_scale(2));
         let mut pattern = TestPattern {
             transform: Mat4::identity(),
         };
@@ -96,4 +150,22 @@ mod tests {
             color![0.75, 0.5, 0.25]
         );
     }
+
+    #[test]
+    fn test_smooth_square_wave() {
+        // Away from the edges, the wave is left as is
+        assert!(is_equal(smooth_square_wave(0.5), 1.0));
+        assert!(is_equal(smooth_square_wave(1.5), -1.0));
+        assert!(is_equal(smooth_square_wave(-0.5), -1.0));
+
+        // Near an edge, it ramps linearly through 0
+        assert!(is_equal(smooth_square_wave(0.95), 0.5));
+        assert!(is_equal(smooth_square_wave(1.05), -0.5));
+        assert!(is_equal(smooth_square_wave(-0.05), -0.5));
+
+        // On an edge, it keeps the value of the side `floor` picks
+        assert_eq!(smooth_square_wave(0.0), 1.0);
+        assert_eq!(smooth_square_wave(1.0), -1.0);
+        assert_eq!(smooth_square_wave(-1.0), -1.0);
+    }
 }
@@ -3,15 +3,26 @@ use crate::color::Color;
 use crate::mat4::Mat4;
 use crate::point::Point;
 
-#[derive(Debug)]
+/// Mixes the colors of two patterns, `ratio` being the weight
+/// of `b`, from 0 (only `a`) to 1 (only `b`).
+#[derive(Debug, Clone)]
 pub struct Blended {
     a: Box<dyn Pattern>,
     b: Box<dyn Pattern>,
+    ratio: f64,
     transform: Mat4,
 }
 
 impl Blended {
     pub fn new<T, U>(a: T, b: U) -> Self
+    where
+        T: Pattern + 'static,
+        U: Pattern + 'static,
+    {
+        Self::with_ratio(a, b, 0.5)
+    }
+
+    pub fn with_ratio<T, U>(a: T, b: U, ratio: f64) -> Self
     where
         T: Pattern + 'static,
         U: Pattern + 'static,
@@ -19,6 +30,7 @@ impl Blended {
         Self {
             a: Box::new(a),
             b: Box::new(b),
+            ratio,
             transform: Mat4::identity(),
         }
     }
@@ -32,19 +44,60 @@ impl Blended {
 
 impl Pattern for Blended {
     fn at(&self, point: Point) -> Color {
-        let color_a = self.a.at(point);
-        let color_b = self.b.at(point);
-        (color_a + color_b) * 0.5
+        let color_a = self.a.at_parent(point);
+        let color_b = self.b.at_parent(point);
+        color_a * (1.0 - self.ratio) + color_b * self.ratio
     }
 
     fn transform(&self) -> &Mat4 {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use crate::pattern::solid::Solid;
+    use crate::pattern::stripe::Stripe;
+    use crate::{color, point};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_at() {
+        // Blended patterns are averaged by default
+        let pattern = Blended::new(Solid::new(Color::WHITE), Solid::new(Color::BLACK));
+        assert_eq!(pattern.at(point![0, 0, 0]), color![0.5, 0.5, 0.5]);
+
+        // The ratio weighs the second pattern
+        let a = color![1, 0.5, 0];
+        let b = color![0, 0.5, 1];
+        let cases = [(0.0, a), (0.25, color![0.75, 0.5, 0.25]), (1.0, b)];
+        for (ratio, result) in cases {
+            let pattern = Blended::with_ratio(Solid::new(a), Solid::new(b), ratio);
+            assert_eq!(pattern.at(point![1, 2, 3]), result);
+        }
+
+        // Blending stripes with rotated stripes cross-hatches them
+        let pattern = Blended::new(
+            Stripe::default(),
+            Stripe::default().set_transform(Mat4::identity().rotate_y(-PI / 2.0)),
+        );
+        let cases = [
+            (point![0.5, 0, 0.5], Color::WHITE),
+            (point![1.5, 0, 0.5], color![0.5, 0.5, 0.5]),
+            (point![0.5, 0, 1.5], color![0.5, 0.5, 0.5]),
+            (point![1.5, 0, 1.5], Color::BLACK),
+        ];
+        for (point, result) in cases {
+            assert_eq!(pattern.at(point), result);
+        }
+    }
+}
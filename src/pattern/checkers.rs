@@ -1,14 +1,15 @@
-use super::Pattern;
+use super::{smooth_square_wave, Pattern};
 use crate::color::Color;
 use crate::mat4::Mat4;
 use crate::point::Point;
 use crate::prelude::is_equal;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Checkers {
     a: Color,
     b: Color,
     transform: Mat4,
+    antialias: bool,
 }
 
 impl Checkers {
@@ -17,6 +18,7 @@ impl Checkers {
             a,
             b,
             transform: Mat4::identity(),
+            antialias: false,
         }
     }
 
@@ -25,6 +27,14 @@ impl Checkers {
 
         self
     }
+
+    /// Averages the checkers over a small neighborhood of each point,
+    /// blending the colors near their edges, like `Stripe::set_antialias`.
+    pub fn set_antialias(mut self, antialias: bool) -> Self {
+        self.antialias = antialias;
+
+        self
+    }
 }
 
 impl Default for Checkers {
@@ -33,12 +43,21 @@ impl Default for Checkers {
             a: Color::WHITE,
             b: Color::BLACK,
             transform: Mat4::identity(),
+            antialias: false,
         }
     }
 }
 
 impl Pattern for Checkers {
     fn at(&self, point: Point) -> Color {
+        if self.antialias {
+            // the checkers are the product of a wave along each axis,
+            // so is their average over a box around the point
+            let wave = smooth_square_wave(point.x)
+                * smooth_square_wave(point.y)
+                * smooth_square_wave(point.z);
+            return self.b.lerp(self.a, (1.0 + wave) / 2.0);
+        }
         if is_equal(
             (point.x.floor() + point.y.floor() + point.z.floor()) % 2.0,
             0.0,
@@ -53,6 +72,10 @@ impl Pattern for Checkers {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
@@ -61,7 +84,7 @@ impl Pattern for Checkers {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::point;
+    use crate::{color, point};
 
     #[test]
     fn test_at() {
@@ -82,5 +105,16 @@ mod tests {
         assert_eq!(pattern.at(point![0, 0, 0]), Color::WHITE);
         assert_eq!(pattern.at(point![0, 0, 0.99]), Color::WHITE);
         assert_eq!(pattern.at(point![0, 0, 1.01]), Color::BLACK);
+
+        // Antialiased checkers blend the colors near the edges,
+        // but not across a floor lying on the edge at y = 0
+        let pattern = Checkers::default().set_antialias(true);
+        assert_eq!(pattern.at(point![0.5, 0, 0.5]), Color::WHITE);
+        assert_eq!(pattern.at(point![1.5, 0, 0.5]), Color::BLACK);
+        assert_eq!(pattern.at(point![0.95, 0, 0.5]), color![0.75, 0.75, 0.75]);
+        assert_eq!(
+            pattern.at(point![0.95, 0, 0.95]),
+            color![0.625, 0.625, 0.625]
+        );
     }
 }
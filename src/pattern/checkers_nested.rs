@@ -4,7 +4,7 @@ use crate::mat4::Mat4;
 use crate::point::Point;
 use crate::prelude::is_equal;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CheckersNested {
     a: Box<dyn Pattern>,
     b: Box<dyn Pattern>,
@@ -37,9 +37,9 @@ impl Pattern for CheckersNested {
             (point.x.floor() + point.y.floor() + point.z.floor()) % 2.0,
             0.0,
         ) {
-            self.a.at(point)
+            self.a.at_parent(point)
         } else {
-            self.b.at(point)
+            self.b.at_parent(point)
         }
     }
 
@@ -47,6 +47,10 @@ impl Pattern for CheckersNested {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
@@ -4,7 +4,7 @@ use crate::mat4::Mat4;
 use crate::pattern::solid::Solid;
 use crate::point::Point;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Gradient {
     a: Color,
     b: Color,
@@ -48,12 +48,16 @@ impl Pattern for Gradient {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct GradientNested {
     a: Box<dyn Pattern>,
     b: Box<dyn Pattern>,
@@ -82,15 +86,19 @@ impl GradientNested {
 
 impl Pattern for GradientNested {
     fn at(&self, point: Point) -> Color {
-        let distance = self.b.at(point) - self.a.at(point);
+        let distance = self.b.at_parent(point) - self.a.at_parent(point);
         let fraction = point.x - point.x.floor();
-        self.a.at(point) + distance * fraction
+        self.a.at_parent(point) + distance * fraction
     }
 
     fn transform(&self) -> &Mat4 {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
@@ -0,0 +1,118 @@
+use super::texture_map::UvPattern;
+use crate::canvas::Canvas;
+use crate::color::Color;
+use std::fmt;
+
+/// Samples an image, such as one read with `Canvas::from_ppm`,
+/// taking the nearest pixel, without any filtering.
+///
+/// `u` goes from the left of the image (0) to its right (1), and wraps
+/// around, so `u = 1` samples the same column as `u = 0`. `v` goes
+/// from the bottom of the image (0) to its top (1), and is clamped,
+/// so the poles of a sphere take the color of the top or bottom row.
+///
+/// `spherical_map` has `v` going downward, so an image mapped with it
+/// is turned upside down, unless the pattern is flipped by a transform
+/// scaling y by -1.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// let mut canvas = Canvas::new(2, 2);
+/// canvas[(0, 0)] = color![1, 0, 0];
+/// canvas[(1, 1)] = color![0, 0, 1];
+/// let texture = ImageTexture::new(canvas);
+/// assert_eq!(texture.uv_at(0.25, 0.75), color![1, 0, 0]);
+/// assert_eq!(texture.uv_at(0.75, 0.25), color![0, 0, 1]);
+/// ```
+#[derive(Clone)]
+pub struct ImageTexture {
+    canvas: Canvas,
+}
+
+impl ImageTexture {
+    pub fn new(canvas: Canvas) -> Self {
+        Self { canvas }
+    }
+}
+
+impl UvPattern for ImageTexture {
+    fn uv_at(&self, u: f64, v: f64) -> Color {
+        let width = self.canvas.width;
+        let height = self.canvas.height;
+        let x = (u.rem_euclid(1.0) * width as f64) as usize;
+        let y = ((1.0 - v.clamp(0.0, 1.0)) * height as f64) as usize;
+        // rounding can land on the last edge, with u just below 0
+        self.canvas[(x.min(width - 1), y.min(height - 1))]
+    }
+
+    fn clone_box(&self) -> Box<dyn UvPattern> {
+        Box::new(self.clone())
+    }
+}
+
+impl fmt::Debug for ImageTexture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ImageTexture")
+            .field("width", &self.canvas.width)
+            .field("height", &self.canvas.height)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color;
+    use crate::pattern::texture_map::{spherical_map, TextureMap};
+    use crate::pattern::Pattern;
+    use crate::point;
+    use crate::point::Point;
+
+    fn texture() -> ImageTexture {
+        // a 10 by 10 image, each pixel colored by its position
+        let mut canvas = Canvas::new(10, 10);
+        for y in 0..10 {
+            for x in 0..10 {
+                canvas[(x, y)] = color![x as f64 / 10.0, y as f64 / 10.0, 0];
+            }
+        }
+        ImageTexture::new(canvas)
+    }
+
+    #[test]
+    fn test_uv_at() {
+        // Sampling the nearest pixel, with v flipped
+        let texture = texture();
+        let cases = [
+            (0.0, 0.0, (0, 9)),
+            (0.3, 0.0, (3, 9)),
+            (0.6, 0.3, (6, 7)),
+            (0.99, 0.99, (9, 0)),
+        ];
+        for (u, v, (x, y)) in cases {
+            assert_eq!(texture.uv_at(u, v), texture.canvas[(x, y)]);
+        }
+
+        // u wraps around, 1 sampling the same column as 0
+        assert_eq!(texture.uv_at(1.0, 0.5), texture.uv_at(0.0, 0.5));
+        assert_eq!(texture.uv_at(1.25, 0.5), texture.uv_at(0.25, 0.5));
+        assert_eq!(texture.uv_at(-0.25, 0.5), texture.uv_at(0.75, 0.5));
+        assert_eq!(texture.uv_at(-1e-17, 0.5), texture.canvas[(9, 5)]);
+
+        // v is clamped to the top and bottom rows
+        assert_eq!(texture.uv_at(0.5, 1.0), texture.canvas[(5, 0)]);
+        assert_eq!(texture.uv_at(0.5, 1.5), texture.canvas[(5, 0)]);
+        assert_eq!(texture.uv_at(0.5, -0.5), texture.canvas[(5, 9)]);
+    }
+
+    #[test]
+    fn test_at() {
+        // An image wrapped on a sphere, the poles taking the edge rows
+        let pattern = TextureMap::new(Box::new(texture()), spherical_map);
+        assert_eq!(pattern.at(point![0, 1, 0]), color![0.5, 0.9, 0]);
+        assert_eq!(pattern.at(point![0, -1, 0]), color![0.5, 0, 0]);
+        assert_eq!(pattern.at(point![0, 0, -1]), color![0.2, 0.5, 0]);
+    }
+}
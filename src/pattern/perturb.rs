@@ -3,24 +3,81 @@ use crate::color::Color;
 use crate::mat4::Mat4;
 use crate::point::Point;
 use noise::{NoiseFn, Perlin};
+use std::fmt;
 
-#[derive(Debug)]
+/// Jitters the points a pattern is looked up at with Perlin noise.
+///
+/// The noise sums `octaves` samples, each at twice the frequency and
+/// `persistence` times the amplitude of the previous one, and is scaled
+/// so points move by at most `scale` along each axis. With the defaults,
+/// a single octave scaled by 0.2, the distortion is smooth.
+#[derive(Clone)]
 pub struct Perturb {
     pattern: Box<dyn Pattern>,
     transform: Mat4,
+    seed: u32,
     perlin: Perlin,
+    octaves: usize,
+    persistence: f64,
+    scale: f64,
 }
 
 impl Perturb {
     pub fn new<T>(pattern: T) -> Self
+    where
+        T: Pattern + 'static,
+    {
+        Self::with_seed(pattern, Perlin::DEFAULT_SEED)
+    }
+
+    /// Perturbs `pattern` with the noise generated from `seed`,
+    /// so differently seeded patterns are distorted differently.
+    pub fn with_seed<T>(pattern: T, seed: u32) -> Self
     where
         T: Pattern + 'static,
     {
         Self {
             pattern: Box::new(pattern),
             transform: Mat4::identity(),
-            perlin: Perlin::default(),
+            seed,
+            perlin: Perlin::new(seed),
+            octaves: 1,
+            persistence: 0.5,
+            scale: 0.2,
+        }
+    }
+
+    /// Sets how many octaves of noise are summed, the amplitude
+    /// of each relative to the previous one, and the largest offset.
+    pub fn set_noise(mut self, octaves: usize, persistence: f64, scale: f64) -> Self {
+        self.octaves = octaves;
+        self.persistence = persistence;
+        self.scale = scale;
+
+        self
+    }
+
+    /// Returns the noise at `point`, summed over octaves, between -1 and 1.
+    fn noise(&self, point: Point) -> f64 {
+        let mut total = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        let mut max = 0.0;
+        for _ in 0..self.octaves {
+            let sample = [
+                point.x * frequency,
+                point.y * frequency,
+                point.z * frequency,
+            ];
+            total += self.perlin.get(sample) * amplitude;
+            max += amplitude;
+            frequency *= 2.0;
+            amplitude *= self.persistence;
         }
+        if max == 0.0 {
+            return 0.0;
+        }
+        total / max
     }
 
     pub fn set_transform(mut self, transform: Mat4) -> Self {
@@ -32,21 +89,91 @@ impl Perturb {
 
 impl Pattern for Perturb {
     fn at(&self, point: Point) -> Color {
-        let noise = self.perlin.get([point.x, point.y, point.z]);
+        let offset = self.noise(point) * self.scale;
         let point = Point {
-            x: point.x + noise * 0.2,
-            y: point.y + noise * 0.2,
-            z: point.z + noise * 0.2,
+            x: point.x + offset,
+            y: point.y + offset,
+            z: point.z + offset,
         };
 
-        self.pattern.at(point)
+        self.pattern.at_parent(point)
     }
 
     fn transform(&self) -> &Mat4 {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
 }
+
+impl fmt::Debug for Perturb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the permutation table of the noise is left out, the seed is enough
+        f.debug_struct("Perturb")
+            .field("pattern", &self.pattern)
+            .field("transform", &self.transform)
+            .field("seed", &self.seed)
+            .field("octaves", &self.octaves)
+            .field("persistence", &self.persistence)
+            .field("scale", &self.scale)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::TestPattern;
+    use crate::{color, point};
+
+    fn test_pattern() -> TestPattern {
+        TestPattern {
+            transform: Mat4::identity(),
+        }
+    }
+
+    #[test]
+    fn test_at() {
+        // By default, points move by a single octave of noise scaled by 0.2
+        let pattern = Perturb::new(test_pattern());
+        let point = point![0.3, 1.7, -2.2];
+        let offset = Perlin::default().get([0.3, 1.7, -2.2]) * 0.2;
+        assert!(offset != 0.0);
+        assert_eq!(
+            pattern.at(point),
+            color![0.3 + offset, 1.7 + offset, -2.2 + offset]
+        );
+
+        // More octaves add finer detail
+        let rough = Perturb::new(test_pattern()).set_noise(4, 0.5, 0.2);
+        assert_ne!(rough.at(point), pattern.at(point));
+
+        // Offsets never exceed the scale
+        let pattern = Perturb::new(test_pattern()).set_noise(6, 0.8, 0.5);
+        for i in 0..100 {
+            let point = point![i as f64 * 0.37, i as f64 * -0.11, i as f64 * 0.05];
+            let color = pattern.at(point);
+            assert!((color.red - point.x).abs() <= 0.5);
+        }
+
+        // Different seeds perturb the same pattern differently
+        let first = Perturb::with_seed(test_pattern(), 1);
+        let second = Perturb::with_seed(test_pattern(), 2);
+        assert_ne!(first.at(point), second.at(point));
+        assert_eq!(
+            Perturb::with_seed(test_pattern(), 1).at(point),
+            first.at(point)
+        );
+        assert!(format!("{:?}", second).contains("seed: 2"));
+
+        // Without octaves, the pattern is left untouched
+        let pattern = Perturb::new(test_pattern()).set_noise(0, 0.5, 0.2);
+        assert_eq!(pattern.at(point), color![0.3, 1.7, -2.2]);
+    }
+}
@@ -5,7 +5,7 @@ use crate::pattern::solid::Solid;
 use crate::point::Point;
 
 /// Interpolates between two colors radially.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RadialGradient {
     a: Color,
     b: Color,
@@ -50,12 +50,16 @@ impl Pattern for RadialGradient {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RadialGradientNested {
     a: Box<dyn Pattern>,
     b: Box<dyn Pattern>,
@@ -84,16 +88,20 @@ impl RadialGradientNested {
 
 impl Pattern for RadialGradientNested {
     fn at(&self, point: Point) -> Color {
-        let distance = self.b.at(point) - self.a.at(point);
+        let distance = self.b.at_parent(point) - self.a.at_parent(point);
         let fraction = ((point.x.powf(2.0) + point.z.powf(2.0)).sqrt())
             - ((point.x.powf(2.0) + point.z.powf(2.0)).sqrt().floor());
-        self.a.at(point) + distance * fraction
+        self.a.at_parent(point) + distance * fraction
     }
 
     fn transform(&self) -> &Mat4 {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
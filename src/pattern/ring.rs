@@ -5,7 +5,7 @@ use crate::pattern::solid::Solid;
 use crate::point::Point;
 use crate::prelude::is_equal;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Ring {
     a: Color,
     b: Color,
@@ -54,12 +54,16 @@ impl Pattern for Ring {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RingNested {
     a: Box<dyn Pattern>,
     b: Box<dyn Pattern>,
@@ -92,9 +96,9 @@ impl Pattern for RingNested {
             (point.x.powf(2.0) + point.z.powf(2.0)).sqrt().floor() % 2.0,
             0.0,
         ) {
-            self.a.at(point)
+            self.a.at_parent(point)
         } else {
-            self.b.at(point)
+            self.b.at_parent(point)
         }
     }
 
@@ -102,6 +106,10 @@ impl Pattern for RingNested {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
@@ -0,0 +1,83 @@
+use super::Pattern;
+use crate::color::Color;
+use crate::mat4::Mat4;
+use crate::point::Point;
+use crate::prelude::is_equal;
+
+/// Alternates between two colors in concentric spherical shells
+/// of unit thickness, around the origin.
+#[derive(Debug, Clone)]
+pub struct Shells {
+    a: Color,
+    b: Color,
+    transform: Mat4,
+}
+
+impl Default for Shells {
+    fn default() -> Self {
+        Self {
+            a: Color::WHITE,
+            b: Color::BLACK,
+            transform: Mat4::identity(),
+        }
+    }
+}
+
+impl Shells {
+    pub fn new(a: Color, b: Color) -> Self {
+        Self {
+            a,
+            b,
+            transform: Mat4::identity(),
+        }
+    }
+
+    pub fn set_transform(mut self, transform: Mat4) -> Self {
+        self.transform = transform;
+
+        self
+    }
+}
+
+impl Pattern for Shells {
+    fn at(&self, point: Point) -> Color {
+        if is_equal((point - Point::zero()).magnitude().floor() % 2.0, 0.0) {
+            self.a
+        } else {
+            self.b
+        }
+    }
+
+    fn transform(&self) -> &Mat4 {
+        &self.transform
+    }
+
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    fn debug_local(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point;
+
+    #[test]
+    fn test_at() {
+        // Shells alternate with the distance from the origin
+        let pattern = Shells::default();
+        assert_eq!(pattern.at(point![0, 0, 0]), Color::WHITE);
+        assert_eq!(pattern.at(point![0.5, 0, 0]), Color::WHITE);
+        assert_eq!(pattern.at(point![0, 1.5, 0]), Color::BLACK);
+        assert_eq!(pattern.at(point![0, 0, 2.5]), Color::WHITE);
+
+        // Shells extend in every direction
+        let pattern = Shells::default();
+        assert_eq!(pattern.at(point![0.3, 0.3, 0.3]), Color::WHITE);
+        assert_eq!(pattern.at(point![0.9, -0.9, 0.9]), Color::BLACK);
+    }
+}
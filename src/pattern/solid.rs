@@ -3,7 +3,7 @@ use crate::color::Color;
 use crate::mat4::Mat4;
 use crate::point::Point;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Solid {
     a: Color,
     transform: Mat4,
@@ -42,6 +42,10 @@ impl Pattern for Solid {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
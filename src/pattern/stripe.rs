@@ -1,15 +1,16 @@
-use super::Pattern;
+use super::{smooth_square_wave, Pattern};
 use crate::color::Color;
 use crate::mat4::Mat4;
 use crate::pattern::solid::Solid;
 use crate::point::Point;
 use crate::prelude::is_equal;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Stripe {
     a: Color,
     b: Color,
     transform: Mat4,
+    antialias: bool,
 }
 
 impl Stripe {
@@ -18,6 +19,7 @@ impl Stripe {
             a,
             b,
             transform: Mat4::identity(),
+            antialias: false,
         }
     }
 
@@ -26,10 +28,32 @@ impl Stripe {
 
         self
     }
+
+    /// Averages the stripes over a small neighborhood of each point,
+    /// blending the colors near their edges instead of switching
+    /// abruptly, which shimmers when the stripes are far away.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let pattern = Stripe::default().set_antialias(true);
+    /// assert_eq!(pattern.at(point![0.5, 0, 0]), Color::WHITE);
+    /// assert_eq!(pattern.at(point![0.95, 0, 0]), color![0.75, 0.75, 0.75]);
+    /// ```
+    pub fn set_antialias(mut self, antialias: bool) -> Self {
+        self.antialias = antialias;
+
+        self
+    }
 }
 
 impl Pattern for Stripe {
     fn at(&self, point: Point) -> Color {
+        if self.antialias {
+            let weight = (1.0 + smooth_square_wave(point.x)) / 2.0;
+            return self.b.lerp(self.a, weight);
+        }
         if is_equal(point.x.floor() % 2.0, 0.0) {
             self.a
         } else {
@@ -41,6 +65,10 @@ impl Pattern for Stripe {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
@@ -52,11 +80,12 @@ impl Default for Stripe {
             a: Color::WHITE,
             b: Color::BLACK,
             transform: Mat4::identity(),
+            antialias: false,
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StripeNested {
     a: Box<dyn Pattern>,
     b: Box<dyn Pattern>,
@@ -86,9 +115,9 @@ impl StripeNested {
 impl Pattern for StripeNested {
     fn at(&self, point: Point) -> Color {
         if is_equal(point.x.floor() % 2.0, 0.0) {
-            self.a.at(point)
+            self.a.at_parent(point)
         } else {
-            self.b.at(point)
+            self.b.at_parent(point)
         }
     }
 
@@ -96,6 +125,10 @@ impl Pattern for StripeNested {
         &self.transform
     }
 
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
     fn debug_local(&self) -> String {
         format!("{:?}", self)
     }
@@ -114,7 +147,7 @@ impl Default for StripeNested {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::point;
+    use crate::{color, point};
 
     #[test]
     fn test_at() {
@@ -138,5 +171,23 @@ mod tests {
         assert_eq!(pattern.at(point!(-0.1, 0, 0)), Color::BLACK);
         assert_eq!(pattern.at(point!(-1, 0, 0)), Color::BLACK);
         assert_eq!(pattern.at(point!(-1.1, 0, 0)), Color::WHITE);
+
+        // An antialiased stripe pattern blends the colors near the edges
+        let pattern = Stripe::default().set_antialias(true);
+        assert_eq!(pattern.at(point!(0.5, 3, -2)), Color::WHITE);
+        assert_eq!(pattern.at(point!(1.5, 0, 0)), Color::BLACK);
+        assert_eq!(pattern.at(point!(0.95, 0, 0)), color![0.75, 0.75, 0.75]);
+        assert_eq!(pattern.at(point!(1.05, 0, 0)), color![0.25, 0.25, 0.25]);
+        assert_eq!(pattern.at(point!(1, 0, 0)), Color::BLACK);
+
+        // Nested patterns go through their own transform
+        let red = color![1, 0, 0];
+        let pattern = StripeNested::new(
+            Stripe::new(red, Color::BLACK).set_transform(Mat4::identity().scale(0.5, 1, 1)),
+            Solid::new(Color::WHITE),
+        );
+        assert_eq!(pattern.at(point!(0.25, 0, 0)), red);
+        assert_eq!(pattern.at(point!(0.75, 0, 0)), Color::BLACK);
+        assert_eq!(pattern.at(point!(1.25, 0, 0)), Color::WHITE);
     }
 }
@@ -0,0 +1,529 @@
+use super::Pattern;
+use crate::color::Color;
+use crate::mat4::Mat4;
+use crate::point::Point;
+use std::f64::consts::PI;
+use std::fmt::Debug;
+
+/// 2D patterns, looked up by `u` and `v` coordinates,
+/// usually between 0 and 1, and applied to shapes by `TextureMap`.
+pub trait UvPattern: Debug + Send + Sync {
+    fn uv_at(&self, u: f64, v: f64) -> Color;
+
+    /// Returns a boxed copy of the pattern, to clone `TextureMap`s.
+    fn clone_box(&self) -> Box<dyn UvPattern>;
+}
+
+impl Clone for Box<dyn UvPattern> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Function turning a point on a surface into `(u, v)` coordinates.
+pub type UvMapping = fn(Point) -> (f64, f64);
+
+/// Maps a point on the surface of a unit sphere to `(u, v)`, with
+/// `u` going around the y axis and `v` from the top (0) to the bottom (1).
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// assert_eq!(spherical_map(point![1, 0, 0]), (0.5, 0.5));
+/// assert_eq!(spherical_map(point![0, 0, -1]), (0.25, 0.5));
+/// assert_eq!(spherical_map(point![0, 1, 0]), (0.5, 0.0));
+/// ```
+pub fn spherical_map(point: Point) -> (f64, f64) {
+    let radius = (point.x * point.x + point.y * point.y + point.z * point.z).sqrt();
+    let u = 0.5 + point.z.atan2(point.x) / (2.0 * PI);
+    let v = (point.y / radius).acos() / PI;
+    (u, v)
+}
+
+/// Maps a point on the xz plane to `(u, v)`, repeating every unit
+/// along x and z, ignoring y so walls aren't checkered by height.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// assert_eq!(planar_map(point![0.25, 0, 0.5]), (0.25, 0.5));
+/// assert_eq!(planar_map(point![1.25, 3, -0.25]), (0.25, 0.75));
+/// ```
+pub fn planar_map(point: Point) -> (f64, f64) {
+    // rem_euclid rather than %, which stays negative for
+    // negative coordinates and would mirror the tiles
+    (point.x.rem_euclid(1.0), point.z.rem_euclid(1.0))
+}
+
+/// Maps a point on the surface of a cylinder around the y axis to
+/// `(u, v)`, with `u` going around the y axis like `spherical_map`,
+/// and `v` going up, repeating every unit of height.
+///
+/// `u` stays below 1, the seam along -x mapping to 0 on both sides,
+/// so patterns tile around the cylinder without a sliver at `u` = 1.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// assert_eq!(cylindrical_map(point![0, 0, -1]), (0.25, 0.0));
+/// assert_eq!(cylindrical_map(point![1, 1.5, 0]), (0.5, 0.5));
+/// assert_eq!(cylindrical_map(point![-1, -0.25, 0]), (0.0, 0.75));
+/// ```
+pub fn cylindrical_map(point: Point) -> (f64, f64) {
+    let u = (0.5 + point.z.atan2(point.x) / (2.0 * PI)).rem_euclid(1.0);
+    let v = point.y - point.y.floor();
+    (u, v)
+}
+
+/// Face of a cube, as picked by `face_from_point`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeFace {
+    Left,
+    Front,
+    Right,
+    Back,
+    Up,
+    Down,
+}
+
+/// Returns the face of an axis-aligned cube from -1 to 1 that
+/// `point` lies on, the one its largest coordinate points to.
+/// Points on an edge or a corner belong to the face of the x axis
+/// first, then of the y axis.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// assert_eq!(face_from_point(point![-1, 0.5, -0.25]), CubeFace::Left);
+/// assert_eq!(face_from_point(point![0.1, 0.6, 0.9]), CubeFace::Front);
+/// // Corners go to the left or right face
+/// assert_eq!(face_from_point(point![1, 1, -1]), CubeFace::Right);
+/// ```
+pub fn face_from_point(point: Point) -> CubeFace {
+    let coord = point.x.abs().max(point.y.abs()).max(point.z.abs());
+    if point.x == coord {
+        CubeFace::Right
+    } else if point.x == -coord {
+        CubeFace::Left
+    } else if point.y == coord {
+        CubeFace::Up
+    } else if point.y == -coord {
+        CubeFace::Down
+    } else if point.z == coord {
+        CubeFace::Front
+    } else {
+        CubeFace::Back
+    }
+}
+
+/// Maps a point of the front face (z = 1) to `(u, v)`, as seen
+/// from outside the cube, `v` going up. The other `cube_uv_*`
+/// functions do the same for their face.
+pub fn cube_uv_front(point: Point) -> (f64, f64) {
+    (face_coord(point.x + 1.0), face_coord(point.y + 1.0))
+}
+
+pub fn cube_uv_back(point: Point) -> (f64, f64) {
+    (face_coord(1.0 - point.x), face_coord(point.y + 1.0))
+}
+
+pub fn cube_uv_left(point: Point) -> (f64, f64) {
+    (face_coord(point.z + 1.0), face_coord(point.y + 1.0))
+}
+
+pub fn cube_uv_right(point: Point) -> (f64, f64) {
+    (face_coord(1.0 - point.z), face_coord(point.y + 1.0))
+}
+
+pub fn cube_uv_up(point: Point) -> (f64, f64) {
+    (face_coord(point.x + 1.0), face_coord(1.0 - point.z))
+}
+
+pub fn cube_uv_down(point: Point) -> (f64, f64) {
+    (face_coord(point.x + 1.0), face_coord(point.z + 1.0))
+}
+
+/// Brings a coordinate offset to the range 0 to 2 back to 0 to 1.
+fn face_coord(offset: f64) -> f64 {
+    offset.rem_euclid(2.0) / 2.0
+}
+
+/// Maps a point on the surface of a cube from -1 to 1 to
+/// its face and the `(u, v)` coordinates on that face.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// assert_eq!(cube_map(point![-0.5, 0.5, 1]), (CubeFace::Front, (0.25, 0.75)));
+/// assert_eq!(cube_map(point![0.5, -1, -0.5]), (CubeFace::Down, (0.75, 0.25)));
+/// ```
+pub fn cube_map(point: Point) -> (CubeFace, (f64, f64)) {
+    let face = face_from_point(point);
+    let uv = match face {
+        CubeFace::Left => cube_uv_left(point),
+        CubeFace::Front => cube_uv_front(point),
+        CubeFace::Right => cube_uv_right(point),
+        CubeFace::Back => cube_uv_back(point),
+        CubeFace::Up => cube_uv_up(point),
+        CubeFace::Down => cube_uv_down(point),
+    };
+    (face, uv)
+}
+
+/// Applies a `UvPattern` to a shape, `mapping` turning
+/// pattern space points into `(u, v)` coordinates.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// let checkers = UvCheckers::new(16.0, 8.0, Color::BLACK, Color::WHITE);
+/// let pattern = TextureMap::new(Box::new(checkers), spherical_map);
+/// assert_eq!(pattern.at(point![0.4315, 0.4670, 0.7719]), Color::BLACK);
+/// assert_eq!(pattern.at(point![-0.9654, 0.2552, -0.0534]), Color::WHITE);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TextureMap {
+    uv_pattern: Box<dyn UvPattern>,
+    mapping: UvMapping,
+    transform: Mat4,
+}
+
+impl TextureMap {
+    pub fn new(uv_pattern: Box<dyn UvPattern>, mapping: UvMapping) -> Self {
+        Self {
+            uv_pattern,
+            mapping,
+            transform: Mat4::identity(),
+        }
+    }
+
+    pub fn set_transform(mut self, transform: Mat4) -> Self {
+        self.transform = transform;
+
+        self
+    }
+}
+
+impl Pattern for TextureMap {
+    fn at(&self, point: Point) -> Color {
+        let (u, v) = (self.mapping)(point);
+        self.uv_pattern.uv_at(u, v)
+    }
+
+    fn transform(&self) -> &Mat4 {
+        &self.transform
+    }
+
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    fn debug_local(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// Applies one `UvPattern` to each face of a cube, with `cube_map`.
+#[derive(Debug, Clone)]
+pub struct CubeMap {
+    faces: [Box<dyn UvPattern>; 6],
+    transform: Mat4,
+}
+
+impl CubeMap {
+    pub fn new(
+        left: Box<dyn UvPattern>,
+        front: Box<dyn UvPattern>,
+        right: Box<dyn UvPattern>,
+        back: Box<dyn UvPattern>,
+        up: Box<dyn UvPattern>,
+        down: Box<dyn UvPattern>,
+    ) -> Self {
+        Self {
+            faces: [left, front, right, back, up, down],
+            transform: Mat4::identity(),
+        }
+    }
+
+    pub fn set_transform(mut self, transform: Mat4) -> Self {
+        self.transform = transform;
+
+        self
+    }
+}
+
+impl Pattern for CubeMap {
+    fn at(&self, point: Point) -> Color {
+        let (face, (u, v)) = cube_map(point);
+        self.faces[face as usize].uv_at(u, v)
+    }
+
+    fn transform(&self) -> &Mat4 {
+        &self.transform
+    }
+
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    fn debug_local(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// Checkers of `a` and `b`, `width` squares across `u`
+/// and `height` squares across `v`.
+#[derive(Debug, Clone)]
+pub struct UvCheckers {
+    pub width: f64,
+    pub height: f64,
+    pub a: Color,
+    pub b: Color,
+}
+
+impl UvCheckers {
+    pub fn new(width: f64, height: f64, a: Color, b: Color) -> Self {
+        Self {
+            width,
+            height,
+            a,
+            b,
+        }
+    }
+}
+
+impl UvPattern for UvCheckers {
+    fn uv_at(&self, u: f64, v: f64) -> Color {
+        let u = (u * self.width).floor();
+        let v = (v * self.height).floor();
+        if (u + v).rem_euclid(2.0) == 0.0 {
+            self.a
+        } else {
+            self.b
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn UvPattern> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::is_equal;
+    use crate::sphere::Sphere;
+    use crate::{color, point};
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn test_spherical_map() {
+        // Using a spherical mapping on a 3D point
+        let cases = [
+            (point![0, 0, -1], (0.25, 0.5)),
+            (point![1, 0, 0], (0.5, 0.5)),
+            (point![0, 0, 1], (0.75, 0.5)),
+            (point![-1, 0, 0], (1.0, 0.5)),
+            (point![0, 1, 0], (0.5, 0.0)),
+            (point![0, -1, 0], (0.5, 1.0)),
+            (point![FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0], (0.5, 0.25)),
+        ];
+        for (point, (u, v)) in cases {
+            let result = spherical_map(point);
+            assert!(is_equal(result.0, u));
+            assert!(is_equal(result.1, v));
+        }
+    }
+
+    #[test]
+    fn test_planar_map() {
+        // Using a planar mapping on a 3D point
+        let cases = [
+            (point![0.25, 0, 0.5], (0.25, 0.5)),
+            (point![0.25, 0, -0.25], (0.25, 0.75)),
+            (point![0.25, 0.5, -0.25], (0.25, 0.75)),
+            (point![1.25, 0, 0.5], (0.25, 0.5)),
+            (point![0.25, 0, -1.75], (0.25, 0.25)),
+            (point![1, 0, -1], (0.0, 0.0)),
+            (point![0, 0, 0], (0.0, 0.0)),
+        ];
+        for (point, (u, v)) in cases {
+            let result = planar_map(point);
+            assert!(is_equal(result.0, u));
+            assert!(is_equal(result.1, v));
+        }
+
+        // Checkers tile the plane across the origin
+        let checkers = UvCheckers::new(2.0, 2.0, Color::BLACK, Color::WHITE);
+        let pattern = TextureMap::new(Box::new(checkers), planar_map);
+        let cases = [
+            (point![0.25, 0, 0.25], Color::BLACK),
+            (point![0.75, 0, 0.25], Color::WHITE),
+            (point![-0.25, 0, 0.25], Color::WHITE),
+            (point![-0.75, 0, 0.25], Color::BLACK),
+            (point![-0.25, 0, -0.25], Color::BLACK),
+            (point![-0.25, 7, -0.25], Color::BLACK),
+        ];
+        for (point, color) in cases {
+            assert_eq!(pattern.at(point), color);
+        }
+    }
+
+    #[test]
+    fn test_cylindrical_map() {
+        // Using a cylindrical mapping on a 3D point
+        let cases = [
+            (point![0, 0, -1], (0.25, 0.0)),
+            (point![1, 0.5, 0], (0.5, 0.5)),
+            (point![0, 0.25, 1], (0.75, 0.25)),
+            (point![FRAC_1_SQRT_2, 2.75, -FRAC_1_SQRT_2], (0.375, 0.75)),
+            (point![-FRAC_1_SQRT_2, -0.5, FRAC_1_SQRT_2], (0.875, 0.5)),
+        ];
+        for (point, (u, v)) in cases {
+            let result = cylindrical_map(point);
+            assert!(is_equal(result.0, u));
+            assert!(is_equal(result.1, v));
+        }
+
+        // Both sides of the seam along -x map to u = 0
+        assert_eq!(cylindrical_map(point![-1, 0, 0]).0, 0.0);
+        assert_eq!(cylindrical_map(point![-1, 0, -0.0]).0, 0.0);
+        let (u, _) = cylindrical_map(point![-1, 0, 1e-9]);
+        assert!(u > 0.99 && u < 1.0);
+
+        // Checkers tile around the circumference and up the height
+        let checkers = UvCheckers::new(4.0, 2.0, Color::BLACK, Color::WHITE);
+        let pattern = TextureMap::new(Box::new(checkers), cylindrical_map);
+        let around = [
+            (point![0, 0.25, -1], Color::WHITE),
+            (point![1, 0.25, 0], Color::BLACK),
+            (point![0, 0.25, 1], Color::WHITE),
+            (point![-1, 0.25, 0], Color::BLACK),
+        ];
+        for (point, color) in around {
+            assert_eq!(pattern.at(point), color);
+            assert_ne!(pattern.at(point![point.x, 0.75, point.z]), color);
+            assert_eq!(pattern.at(point![point.x, -1.75, point.z]), color);
+        }
+
+        // the squares on each side of the seam alternate too
+        assert_eq!(pattern.at(point![-1, 0.25, 0.01]), Color::WHITE);
+        assert_eq!(pattern.at(point![-1, 0.25, -0.01]), Color::BLACK);
+    }
+
+    #[test]
+    fn test_face_from_point() {
+        // Identifying the face of a cube from a point
+        let cases = [
+            (point![-1, 0.5, -0.25], CubeFace::Left),
+            (point![1.1, -0.75, 0.8], CubeFace::Right),
+            (point![0.1, 0.6, 0.9], CubeFace::Front),
+            (point![-0.7, 0, -2], CubeFace::Back),
+            (point![0.5, 1, 0.9], CubeFace::Up),
+            (point![-0.2, -1.3, 1.1], CubeFace::Down),
+        ];
+        for (point, face) in cases {
+            assert_eq!(face_from_point(point), face);
+        }
+
+        // Ties on edges and corners favor x, then y
+        let cases = [
+            (point![1, 1, 1], CubeFace::Right),
+            (point![-1, -1, -1], CubeFace::Left),
+            (point![-1, 1, 0], CubeFace::Left),
+            (point![0, 1, -1], CubeFace::Up),
+            (point![0.5, -1, 1], CubeFace::Down),
+        ];
+        for (point, face) in cases {
+            assert_eq!(face_from_point(point), face);
+        }
+    }
+
+    #[test]
+    fn test_cube_uv() {
+        // UV mapping each face of a cube
+        let cases: [(UvMapping, _, _); 6] = [
+            (cube_uv_front, point![-0.5, 0.5, 1], point![0.5, -0.5, 1]),
+            (cube_uv_back, point![0.5, 0.5, -1], point![-0.5, -0.5, -1]),
+            (cube_uv_left, point![-1, 0.5, -0.5], point![-1, -0.5, 0.5]),
+            (cube_uv_right, point![1, 0.5, 0.5], point![1, -0.5, -0.5]),
+            (cube_uv_up, point![-0.5, 1, -0.5], point![0.5, 1, 0.5]),
+            (cube_uv_down, point![-0.5, -1, 0.5], point![0.5, -1, -0.5]),
+        ];
+        for (mapping, first, second) in cases {
+            assert_eq!(mapping(first), (0.25, 0.75));
+            assert_eq!(mapping(second), (0.75, 0.25));
+        }
+
+        // Coordinates stay within 0 to 1 on the edges
+        let (_, (u, v)) = cube_map(point![1, 1, 1]);
+        assert!((0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v));
+    }
+
+    #[test]
+    fn test_cube_map() {
+        // Each face of a cube gets its own pattern
+        let face = |color| Box::new(UvCheckers::new(2.0, 2.0, color, Color::BLACK));
+        let colors = [
+            color![1, 0, 0],
+            color![1, 1, 0],
+            color![1, 0.5, 0],
+            color![0, 1, 0],
+            color![0, 1, 1],
+            color![0, 0, 1],
+        ];
+        let pattern = CubeMap::new(
+            face(colors[0]),
+            face(colors[1]),
+            face(colors[2]),
+            face(colors[3]),
+            face(colors[4]),
+            face(colors[5]),
+        );
+        let points = [
+            point![-1, -0.5, -0.5],
+            point![-0.5, -0.5, 1],
+            point![1, -0.5, 0.5],
+            point![0.5, -0.5, -1],
+            point![-0.5, 1, 0.5],
+            point![-0.5, -1, -0.5],
+        ];
+        for (point, color) in points.into_iter().zip(colors) {
+            assert_eq!(pattern.at(point), color);
+        }
+        assert_eq!(pattern.at(point![0.5, -0.5, 1]), Color::BLACK);
+    }
+
+    #[test]
+    fn test_uv_at() {
+        // Checker pattern in 2D
+        let checkers = UvCheckers::new(2.0, 2.0, Color::BLACK, Color::WHITE);
+        let cases = [
+            (0.0, 0.0, Color::BLACK),
+            (0.5, 0.0, Color::WHITE),
+            (0.0, 0.5, Color::WHITE),
+            (0.5, 0.5, Color::BLACK),
+            (1.0, 1.0, Color::BLACK),
+        ];
+        for (u, v, color) in cases {
+            assert_eq!(checkers.uv_at(u, v), color);
+        }
+    }
+
+    #[test]
+    fn test_at_object() {
+        // Mapping checkers onto a scaled sphere follows its surface
+        let sphere = Sphere::new().set_transform(Mat4::identity().uniform_scale(2));
+        let checkers = UvCheckers::new(4.0, 2.0, Color::BLACK, Color::WHITE);
+        let pattern = TextureMap::new(Box::new(checkers), spherical_map);
+        assert_eq!(pattern.at_object(&sphere, point![2, 0, 0]), Color::WHITE);
+        assert_eq!(pattern.at_object(&sphere, point![0, 0, -2]), Color::BLACK);
+        assert_eq!(pattern.at_object(&sphere, point![0, 0, 2]), Color::BLACK);
+    }
+}
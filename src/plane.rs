@@ -1,20 +1,52 @@
+use crate::bounds::BoundingBox;
 use crate::intersection::Intersection;
 use crate::mat4::Mat4;
 use crate::material::Material;
-use crate::pattern::Pattern;
+use crate::point;
 use crate::point::Point;
 use crate::prelude::EPSILON;
 use crate::prelude::OBJECT_COUNTER;
 use crate::ray::Ray;
-use crate::shape::Shape;
+use crate::shape::{Shape, Transforms};
 use crate::vector::Vector;
 use std::sync::atomic::Ordering;
 
-#[derive(Debug, PartialEq)]
+/// Rectangle a bounded plane is cut to, from `min_x` to `max_x`
+/// and from `min_z` to `max_z`, all inclusive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlaneExtent {
+    pub min_x: f64,
+    pub max_x: f64,
+    pub min_z: f64,
+    pub max_z: f64,
+}
+
+impl PlaneExtent {
+    /// Checks whether `point` lies within the rectangle,
+    /// allowing for rounding on its edges.
+    fn contains(&self, point: Point) -> bool {
+        self.min_x - EPSILON <= point.x
+            && point.x <= self.max_x + EPSILON
+            && self.min_z - EPSILON <= point.z
+            && point.z <= self.max_z + EPSILON
+    }
+}
+
+/// `Plane` instances are the xz plane, infinite unless
+/// an `extent` cuts them to a rectangle.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Plane {
+    /// Deserialized shapes are given a new id.
+    #[cfg_attr(feature = "serde", serde(skip, default = "crate::shape::next_id"))]
     pub id: usize,
-    pub transform: Mat4,
+    transforms: Transforms,
     pub material: Material,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub label: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub extent: Option<PlaneExtent>,
 }
 
 impl Plane {
@@ -23,13 +55,29 @@ impl Plane {
     }
 
     pub fn set_transform(mut self, transform: Mat4) -> Self {
-        self.transform = transform;
+        self.transforms.set_transform(transform);
 
         self
     }
 
-    pub fn set_pattern(mut self, pattern: Box<dyn Pattern>) -> Self {
-        self.material.pattern = Some(pattern);
+    /// Cuts the plane to the rectangle from `min_x` to `max_x`
+    /// and from `min_z` to `max_z`, as for a tabletop.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let table = Plane::new().set_extent(-1.0, 1.0, -0.5, 0.5);
+    /// assert_eq!(table.bounds().min, point![-1, 0, -0.5]);
+    /// assert_eq!(table.bounds().max, point![1, 0, 0.5]);
+    /// ```
+    pub fn set_extent(mut self, min_x: f64, max_x: f64, min_z: f64, max_z: f64) -> Self {
+        self.extent = Some(PlaneExtent {
+            min_x,
+            max_x,
+            min_z,
+            max_z,
+        });
 
         self
     }
@@ -45,19 +93,60 @@ impl Shape for Plane {
         }
     }
 
-    fn local_intersect(&self, ray: Ray) -> Vec<Intersection> {
+    /// Hits outside of the extent of a bounded plane are discarded.
+    fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection<'_>> {
+        let mut intersections = Vec::new();
+        self.local_intersect_into(local_ray, &mut intersections);
+        intersections
+    }
+
+    fn local_intersect_into<'s>(&'s self, ray: Ray, intersections: &mut Vec<Intersection<'s>>) {
         if ray.direction.y.abs() < EPSILON {
-            return Vec::new();
-        } else {
-            vec![Intersection {
-                t: -ray.origin.y / ray.direction.y,
-                object: self,
-            }]
+            return;
+        }
+        let t = -ray.origin.y / ray.direction.y;
+        if let Some(extent) = self.extent {
+            if !extent.contains(ray.position(t)) {
+                return;
+            }
         }
+        intersections.push(Intersection::new(t, self));
     }
 
-    fn transform(&self) -> &Mat4 {
-        &self.transform
+    /// Planes are infinite along x and z unless bounded,
+    /// and flat along y.
+    fn bounds(&self) -> BoundingBox {
+        match self.extent {
+            Some(extent) => BoundingBox::new(
+                point![extent.min_x, 0, extent.min_z],
+                point![extent.max_x, 0, extent.max_z],
+            ),
+            None => BoundingBox::new(
+                point![f64::NEG_INFINITY, 0, f64::NEG_INFINITY],
+                point![f64::INFINITY, 0, f64::INFINITY],
+            ),
+        }
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn set_parent_transform(&mut self, transform: Mat4) {
+        self.transforms.set_parent(transform);
+    }
+
+    #[cfg(feature = "serde")]
+    fn serializable(&self) -> Option<crate::world::SerializableShape<'_>> {
+        Some(crate::world::SerializableShape::Plane(self))
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn label_mut(&mut self) -> Option<&mut Option<String>> {
+        Some(&mut self.label)
     }
 
     fn material(&self) -> &Material {
@@ -68,6 +157,10 @@ impl Shape for Plane {
         &mut self.material
     }
 
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
     fn debug(&self) -> String {
         format!("{:?}", self)
     }
@@ -81,8 +174,10 @@ impl Default for Plane {
     fn default() -> Self {
         Self {
             id: OBJECT_COUNTER.fetch_add(1, Ordering::Relaxed),
-            transform: Mat4::identity(),
+            transforms: Transforms::default(),
             material: Material::new(),
+            label: None,
+            extent: None,
         }
     }
 }
@@ -144,4 +239,42 @@ mod tests {
         assert!(is_equal(intersections[0].t, 1.0));
         assert_eq!(intersections[0].object.id(), plane.id);
     }
+
+    #[test]
+    fn test_extent() {
+        let plane = Plane::new().set_extent(-1.0, 2.0, -3.0, 4.0);
+        let hit = |x: f64, z: f64| {
+            let ray = Ray {
+                origin: point![x, 1, z],
+                direction: vector![0, -1, 0],
+            };
+            plane.local_intersect(ray).len() == 1
+        };
+
+        // Rays within the extent hit the plane, those outside miss it
+        assert!(hit(0.0, 0.0));
+        assert!(hit(1.9, -2.9));
+        assert!(!hit(2.1, 0.0));
+        assert!(!hit(0.0, -3.1));
+        assert!(!hit(-5.0, 10.0));
+
+        // Edges are inclusive, on every side
+        assert!(hit(2.0, 0.0));
+        assert!(hit(-1.0, 0.0));
+        assert!(hit(0.0, 4.0));
+        assert!(hit(0.0, -3.0));
+        assert!(hit(2.0, 4.0));
+
+        // including for slanted rays, despite rounding
+        let ray = Ray {
+            origin: point![0.3, 0.7, 0],
+            direction: vector![1.7, -0.7, 0],
+        };
+        assert_eq!(plane.local_intersect(ray).len(), 1);
+
+        // A bounded plane has finite bounds
+        let bounds = plane.bounds();
+        assert_eq!(bounds.min, point![-1, 0, -3]);
+        assert_eq!(bounds.max, point![2, 0, 4]);
+    }
 }
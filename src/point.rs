@@ -1,9 +1,10 @@
 use crate::prelude::is_equal;
 use crate::vector::Vector;
-use std::ops::{Add, Sub};
+use std::ops::{Add, Neg, Sub};
 
 /// Point in 3D space
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     pub x: f64,
     pub y: f64,
@@ -47,6 +48,50 @@ impl Point {
             z: 0.0,
         }
     }
+
+    /// Linear interpolation, `self` at `t` = 0 and `other` at `t` = 1.
+    /// `t` isn't clamped, values outside of [0, 1] extrapolate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let (a, b) = (point![0, 0, 0], point![2, 4, -2]);
+    /// assert_eq!(a.lerp(b, 0.25), point![0.5, 1, -0.5]);
+    /// assert_eq!(a.lerp(b, 1.5), point![3, 6, -3]);
+    /// ```
+    pub fn lerp(&self, other: Self, t: f64) -> Self {
+        *self + (other - *self) * t
+    }
+
+    /// Distance between two points.
+    pub fn distance(&self, other: Self) -> f64 {
+        (other - *self).magnitude()
+    }
+
+    /// Point halfway between two points.
+    pub fn midpoint(&self, other: Self) -> Self {
+        self.lerp(other, 0.5)
+    }
+
+    /// Coordinates of the point, as `[x, y, z]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let [x, y, z] = point![1, -2, 3].as_array();
+    /// assert_eq!((x, y, z), (1.0, -2.0, 3.0));
+    /// assert_eq!(Point::from_array([x, y, z]), point![1, -2, 3]);
+    /// ```
+    pub fn as_array(&self) -> [f64; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    /// Creates a Point from its coordinates, as `[x, y, z]`.
+    pub fn from_array([x, y, z]: [f64; 3]) -> Self {
+        Self { x, y, z }
+    }
 }
 
 impl PartialEq for Point {
@@ -67,6 +112,19 @@ impl Add<Vector> for Point {
     }
 }
 
+/// Point mirrored through the origin
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
 impl Sub for Point {
     type Output = Vector;
 
@@ -94,8 +152,8 @@ impl Sub<Vector> for Point {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::point::Point;
     use crate::vector;
-    use crate::{point, point::Point};
 
     #[test]
     fn test_eq() {
@@ -109,6 +167,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_as_array() {
+        let point = point![1.5, -2, 0.25];
+        assert_eq!(point.as_array(), [1.5, -2.0, 0.25]);
+        assert_eq!(Point::from_array(point.as_array()), point);
+        assert_eq!(Point::from_array([0.0; 3]), Point::zero());
+    }
+
+    #[test]
+    fn test_neg() {
+        assert_eq!(-point![1, -2, 3], point![-1, 2, -3]);
+        assert_eq!(-Point::zero(), Point::zero());
+    }
+
+    #[test]
+    fn test_lerp() {
+        let (a, b) = (point![1, -2, 3], point![3, 2, -1]);
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 0.5), point![2, 0, 1]);
+        assert_eq!(a.lerp(b, 1.0), b);
+    }
+
+    #[test]
+    fn test_distance() {
+        let (a, b) = (point![0, 0, 0], point![3, 4, 0]);
+        assert!(is_equal(a.distance(b), 5.0));
+        assert!(is_equal(b.distance(a), 5.0));
+        assert!(is_equal(a.distance(a), 0.0));
+        assert_eq!(a.midpoint(b), point![1.5, 2, 0]);
+    }
+
     #[test]
     fn test_add_vector() {
         assert_eq!(
@@ -1,28 +1,48 @@
-pub use crate::camera::Camera;
+pub use crate::bounds::BoundingBox;
+pub use crate::camera::{Camera, Projection, Sampling};
+pub use crate::canvas::{Canvas, PpmError};
 pub use crate::color;
-pub use crate::color::Color;
-pub use crate::light::Light;
+pub use crate::color::{Color, ColorError};
+pub use crate::cone::Cone;
+pub use crate::csg::{Csg, Operation};
+pub use crate::cube::Cube;
+pub use crate::cylinder::Cylinder;
+pub use crate::ground::Ground;
+pub use crate::group::Group;
+pub use crate::light::{AreaLight, DirectionalLight, Light, PointLight, Spotlight};
 pub use crate::mat4::Mat4;
 pub use crate::material::Material;
+pub use crate::matrix_nn::Matrix;
 pub use crate::pattern::{
     blended::Blended,
     checkers::Checkers,
     checkers_nested::CheckersNested,
     gradient::{Gradient, GradientNested},
+    image_texture::ImageTexture,
     perturb::Perturb,
     radial_gradient::{RadialGradient, RadialGradientNested},
     ring::{Ring, RingNested},
+    shells::Shells,
     solid::Solid,
     stripe::{Stripe, StripeNested},
+    texture_map::{
+        cube_map, cube_uv_back, cube_uv_down, cube_uv_front, cube_uv_left, cube_uv_right,
+        cube_uv_up, cylindrical_map, face_from_point, planar_map, spherical_map, CubeFace, CubeMap,
+        TextureMap, UvCheckers, UvMapping, UvPattern,
+    },
     Pattern,
 };
-pub use crate::plane::Plane;
+pub use crate::plane::{Plane, PlaneExtent};
 pub use crate::point;
 pub use crate::point::Point;
+pub use crate::scene::{load_scene, SceneError};
+pub use crate::shape::{Shape, ShapeBuilder};
 pub use crate::sphere::Sphere;
+pub use crate::stats::RenderStats;
+pub use crate::triangle::{SmoothTriangle, Triangle};
 pub use crate::vector;
 pub use crate::vector::Vector;
-pub use crate::world::World;
+pub use crate::world::{World, WorldBuilder};
 
 use std::sync::atomic::AtomicUsize;
 
@@ -30,7 +50,54 @@ pub static OBJECT_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 pub const EPSILON: f64 = 0.00001;
 
-/// Float numbers comparison.
+/// Float numbers comparison, within `EPSILON`.
 pub fn is_equal(lhs: f64, rhs: f64) -> bool {
-    (lhs - rhs).abs() < EPSILON
+    is_equal_with(lhs, rhs, EPSILON)
+}
+
+/// Float numbers comparison, within an absolute tolerance of `epsilon`.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// assert!(is_equal_with(1.0, 1.05, 0.1));
+/// assert!(!is_equal_with(1.0, 1.05, 0.01));
+/// ```
+pub fn is_equal_with(lhs: f64, rhs: f64, epsilon: f64) -> bool {
+    (lhs - rhs).abs() < epsilon
+}
+
+/// Float numbers comparison, within `rel` times the larger of
+/// both magnitudes, so large numbers are compared by their leading
+/// digits. Numbers within `EPSILON` of each other, such as those
+/// close to 0, are always equal.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// assert!(is_equal_rel(1000.0, 1000.001, 1e-5));
+/// assert!(!is_equal(1000.0, 1000.001));
+/// assert!(!is_equal_rel(1.0, 1.001, 1e-5));
+/// assert!(is_equal_rel(0.0, 1e-6, 1e-5));
+/// ```
+pub fn is_equal_rel(lhs: f64, rhs: f64, rel: f64) -> bool {
+    let difference = (lhs - rhs).abs();
+    difference < EPSILON || difference <= rel * lhs.abs().max(rhs.abs())
+}
+
+/// Returns the tolerance for values of about `magnitude`,
+/// `EPSILON` up to a magnitude of 1, and growing with it beyond,
+/// as float precision does.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// assert_eq!(epsilon_for(0.5), EPSILON);
+/// assert!(is_equal_with(epsilon_for(-1000.0), EPSILON * 1000.0, 1e-12));
+/// ```
+pub fn epsilon_for(magnitude: f64) -> f64 {
+    EPSILON * magnitude.abs().max(1.0)
 }
@@ -31,7 +31,7 @@ impl Ray {
     /// Translating a ray
     pub fn transform(&self, transform: Mat4) -> Self {
         Ray {
-            origin: &transform * self.origin,
+            origin: transform * self.origin,
             direction: transform * self.direction,
         }
     }
@@ -75,5 +75,16 @@ mod tests {
         let ray_transformed = ray.transform(transform);
         assert_eq!(ray_transformed.origin, point![2, 6, 12]);
         assert_eq!(ray_transformed.direction, vector![0, 3, 0]);
+
+        // Moving a ray into camera space with a view transformation
+        let ray = Ray {
+            origin: point![0, 0, 8],
+            direction: vector![0, 0, -1],
+        };
+        let transform =
+            Mat4::identity().view_transform(point![0, 0, 8], point![0, 0, 0], vector![0, 1, 0]);
+        let ray_transformed = ray.transform(transform);
+        assert_eq!(ray_transformed.origin, point![0, 0, 0]);
+        assert_eq!(ray_transformed.direction, vector![0, 0, -1]);
     }
 }
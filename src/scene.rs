@@ -0,0 +1,953 @@
+use crate::camera::Camera;
+use crate::color::Color;
+use crate::cube::Cube;
+use crate::light::Light;
+use crate::mat4::Mat4;
+use crate::material::Material;
+use crate::plane::Plane;
+use crate::point::Point;
+use crate::shape::Shape;
+use crate::sphere::Sphere;
+use crate::vector::Vector;
+use crate::world::World;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Error returned by `load_scene`, with the line it was found on,
+/// counting from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SceneError {
+    /// The line is not part of the YAML subset scenes are written in.
+    Syntax(usize, String),
+    /// A key that is not understood where it appears.
+    UnknownKey(usize, String),
+    /// A value of the wrong kind.
+    InvalidValue(usize, String),
+    /// A name that no earlier `define` entry introduced.
+    Undefined(usize, String),
+    /// A required key is missing from the entry starting on the line.
+    MissingKey(usize, String),
+    /// The scene has no `add: camera` entry.
+    MissingCamera,
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Syntax(line, message) => write!(f, "line {}: {}", line, message),
+            Self::UnknownKey(line, key) => write!(f, "line {}: unknown key {:?}", line, key),
+            Self::InvalidValue(line, message) => write!(f, "line {}: {}", line, message),
+            Self::Undefined(line, name) => write!(f, "line {}: {:?} is not defined", line, name),
+            Self::MissingKey(line, key) => write!(f, "line {}: missing key {:?}", line, key),
+            Self::MissingCamera => write!(f, "scene has no camera"),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+/// Builds the camera and world of a scene described in YAML,
+/// in the format of the scenes shipped with the book.
+///
+/// The scene is a list of entries, each either adding an element
+/// with `add: camera`, `add: light`, `add: sphere`, `add: plane` or
+/// `add: cube`, or naming a material or a transform with `define`,
+/// for later entries to refer to. Material definitions may `extend`
+/// an earlier one. Transforms are lists of `[translate, x, y, z]`,
+/// `[scale, x, y, z]`, `[rotate-x, rad]`, `[rotate-y, rad]`,
+/// `[rotate-z, rad]`, `[shear, xy, xz, yx, yz, zx, zy]` or defined
+/// names, applied in order.
+///
+/// Materials accept `reflective`, `transparency` and `refractive-index`,
+/// which are ignored as the tracer has neither reflection nor refraction.
+/// Any other key that isn't understood is an error.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// # use trace::scene::load_scene;
+/// let (camera, world) = load_scene(
+///     "
+/// - add: camera
+///   width: 100
+///   height: 50
+///   field-of-view: 0.785
+///   from: [0, 1.5, -5]
+///   to: [0, 1, 0]
+///   up: [0, 1, 0]
+///
+/// - add: light
+///   at: [-10, 10, -10]
+///   intensity: [1, 1, 1]
+///
+/// - add: sphere
+///   material:
+///     color: [1, 0.2, 1]
+///   transform:
+///     - [translate, 0, 1, 0]
+/// ",
+/// )
+/// .unwrap();
+/// assert_eq!(camera.hsize, 100);
+/// assert_eq!(world.lights.len(), 1);
+/// assert_eq!(world.objects[0].material().color, color![1, 0.2, 1]);
+///
+/// // Unknown keys are reported with their line
+/// let error = load_scene("- add: light\n  at: [0, 0, 0]\n  colour: [1, 1, 1]\n");
+/// assert_eq!(error.err(), Some(SceneError::UnknownKey(3, "colour".to_string())));
+/// ```
+pub fn load_scene(yaml: &str) -> Result<(Camera, World), SceneError> {
+    let mut camera = None;
+    let mut world = World {
+        lights: Vec::new(),
+        objects: Vec::new(),
+        background: Color::BLACK,
+        ambient_occlusion_samples: 0,
+        ambient_occlusion_distance: 1.0,
+    };
+    let mut definitions = HashMap::new();
+    let root = match parse_yaml(yaml)? {
+        Some(root) => root,
+        None => return Err(SceneError::MissingCamera),
+    };
+    for item in root.list()? {
+        let entries = item.map()?;
+        if let Some(kind) = find(entries, "add") {
+            match kind.scalar()? {
+                "camera" => camera = Some(parse_camera(item)?),
+                "light" => world.lights.push(parse_light(item)?),
+                "sphere" | "plane" | "cube" => {
+                    world.objects.push(parse_shape(item, &definitions)?);
+                }
+                other => {
+                    return Err(SceneError::InvalidValue(
+                        kind.line,
+                        format!("unknown element {:?}", other),
+                    ))
+                }
+            }
+        } else if let Some(name) = find(entries, "define") {
+            let definition = parse_definition(item, &definitions)?;
+            definitions.insert(name.scalar()?.to_string(), definition);
+        } else {
+            return Err(SceneError::MissingKey(item.line, "add".to_string()));
+        }
+    }
+    match camera {
+        Some(camera) => Ok((camera, world)),
+        None => Err(SceneError::MissingCamera),
+    }
+}
+
+/// What a `define` entry names.
+enum Definition {
+    /// The entries of a material, those of any extended material first.
+    Material(Vec<Entry>),
+    Transform(Mat4),
+}
+
+fn parse_camera(item: &Node) -> Result<Camera, SceneError> {
+    let (mut width, mut height, mut field_of_view) = (None, None, None);
+    let (mut from, mut to, mut up) = (None, None, None);
+    for entry in item.map()? {
+        let value = &entry.value;
+        match entry.key.as_str() {
+            "add" => {}
+            "width" => width = Some(value.size()?),
+            "height" => height = Some(value.size()?),
+            "field-of-view" => field_of_view = Some(value.number()?),
+            "from" => from = Some(value.point()?),
+            "to" => to = Some(value.point()?),
+            "up" => up = Some(value.vector()?),
+            _ => return Err(entry.unknown()),
+        }
+    }
+    let mut camera = Camera::new(
+        required(width, item, "width")?,
+        required(height, item, "height")?,
+        required(field_of_view, item, "field-of-view")?,
+    );
+    camera.transform = Mat4::identity().view_transform(
+        required(from, item, "from")?,
+        required(to, item, "to")?,
+        required(up, item, "up")?,
+    );
+    Ok(camera)
+}
+
+fn parse_light(item: &Node) -> Result<Light, SceneError> {
+    let (mut position, mut intensity) = (None, None);
+    for entry in item.map()? {
+        match entry.key.as_str() {
+            "add" => {}
+            "at" => position = Some(entry.value.point()?),
+            "intensity" => intensity = Some(entry.value.color()?),
+            _ => return Err(entry.unknown()),
+        }
+    }
+    Ok(Light::point(
+        required(position, item, "at")?,
+        required(intensity, item, "intensity")?,
+    ))
+}
+
+fn parse_shape(
+    item: &Node,
+    definitions: &HashMap<String, Definition>,
+) -> Result<Box<dyn Shape>, SceneError> {
+    let mut material = Material::new();
+    let mut transform = Mat4::identity();
+    let mut casts_shadow = true;
+    for entry in item.map()? {
+        match entry.key.as_str() {
+            "add" => {}
+            "material" => material = parse_material(&entry.value, definitions)?,
+            "transform" => transform = parse_transform(&entry.value, definitions)?,
+            "shadow" => casts_shadow = entry.value.boolean()?,
+            _ => return Err(entry.unknown()),
+        }
+    }
+    let mut shape: Box<dyn Shape> = match find(item.map()?, "add").unwrap().scalar()? {
+        "sphere" => Box::new(Sphere::new().set_transform(transform)),
+        "plane" => Box::new(Plane::new().set_transform(transform)),
+        _ => Box::new(Cube::new().set_transform(transform)),
+    };
+    material.casts_shadow = casts_shadow;
+    *shape.material_mut() = material;
+    Ok(shape)
+}
+
+fn parse_definition(
+    item: &Node,
+    definitions: &HashMap<String, Definition>,
+) -> Result<Definition, SceneError> {
+    let (mut extend, mut value) = (None, None);
+    for entry in item.map()? {
+        match entry.key.as_str() {
+            "define" => {}
+            "extend" => extend = Some(&entry.value),
+            "value" => value = Some(&entry.value),
+            _ => return Err(entry.unknown()),
+        }
+    }
+    let value = required(value, item, "value")?;
+    match &value.value {
+        Value::Map(entries) => {
+            let mut all = match extend {
+                Some(name) => material_entries(name, definitions)?.clone(),
+                None => Vec::new(),
+            };
+            all.extend(entries.iter().cloned());
+            // catch mistakes where the material is defined, not where it's used
+            apply_material(&mut Material::new(), &all)?;
+            Ok(Definition::Material(all))
+        }
+        _ => match extend {
+            Some(name) => Err(SceneError::InvalidValue(
+                name.line,
+                "only materials can be extended".to_string(),
+            )),
+            None => Ok(Definition::Transform(parse_transform(value, definitions)?)),
+        },
+    }
+}
+
+/// Returns the material given inline, or named by an earlier definition.
+fn parse_material(
+    node: &Node,
+    definitions: &HashMap<String, Definition>,
+) -> Result<Material, SceneError> {
+    let mut material = Material::new();
+    match &node.value {
+        Value::Map(entries) => apply_material(&mut material, entries)?,
+        _ => apply_material(&mut material, material_entries(node, definitions)?)?,
+    }
+    Ok(material)
+}
+
+fn material_entries<'a>(
+    name: &Node,
+    definitions: &'a HashMap<String, Definition>,
+) -> Result<&'a Vec<Entry>, SceneError> {
+    match definitions.get(name.scalar()?) {
+        Some(Definition::Material(entries)) => Ok(entries),
+        Some(Definition::Transform(_)) => Err(SceneError::InvalidValue(
+            name.line,
+            format!("{:?} is a transform, not a material", name.scalar()?),
+        )),
+        None => Err(SceneError::Undefined(name.line, name.scalar()?.to_string())),
+    }
+}
+
+fn apply_material(material: &mut Material, entries: &[Entry]) -> Result<(), SceneError> {
+    for entry in entries {
+        let value = &entry.value;
+        match entry.key.as_str() {
+            "color" => material.color = value.color()?,
+            "ambient" => material.ambient = value.number()?,
+            "diffuse" => material.diffuse = value.number()?,
+            "specular" => material.specular = value.number()?,
+            "shininess" => material.shininess = value.number()?,
+            "reflective" | "transparency" | "refractive-index" => {
+                value.number()?;
+            }
+            _ => return Err(entry.unknown()),
+        }
+    }
+    Ok(())
+}
+
+/// Combines a list of transforms, each applied after the previous one.
+fn parse_transform(
+    node: &Node,
+    definitions: &HashMap<String, Definition>,
+) -> Result<Mat4, SceneError> {
+    let mut transform = Mat4::identity();
+    for item in node.list()? {
+        transform = match &item.value {
+            Value::Scalar(name) => match definitions.get(name) {
+                Some(Definition::Transform(defined)) => *defined * transform,
+                Some(Definition::Material(_)) => {
+                    return Err(SceneError::InvalidValue(
+                        item.line,
+                        format!("{:?} is a material, not a transform", name),
+                    ))
+                }
+                None => return Err(SceneError::Undefined(item.line, name.clone())),
+            },
+            Value::List(operation) => {
+                let (name, arguments) = match operation.split_first() {
+                    Some((name, arguments)) => (name.scalar()?, arguments),
+                    None => {
+                        return Err(SceneError::InvalidValue(
+                            item.line,
+                            "empty transform".to_string(),
+                        ))
+                    }
+                };
+                let count = match name {
+                    "translate" | "scale" => 3,
+                    "rotate-x" | "rotate-y" | "rotate-z" => 1,
+                    "shear" => 6,
+                    _ => {
+                        return Err(SceneError::InvalidValue(
+                            item.line,
+                            format!("unknown transform {:?}", name),
+                        ))
+                    }
+                };
+                if arguments.len() != count {
+                    return Err(SceneError::InvalidValue(
+                        item.line,
+                        format!(
+                            "{:?} takes {} arguments, found {}",
+                            name,
+                            count,
+                            arguments.len()
+                        ),
+                    ));
+                }
+                let a = arguments
+                    .iter()
+                    .map(Node::number)
+                    .collect::<Result<Vec<_>, _>>()?;
+                match name {
+                    "translate" => transform.translate(a[0], a[1], a[2]),
+                    "scale" => transform.scale(a[0], a[1], a[2]),
+                    "rotate-x" => transform.rotate_x(a[0]),
+                    "rotate-y" => transform.rotate_y(a[0]),
+                    "rotate-z" => transform.rotate_z(a[0]),
+                    _ => transform.shear(a[0], a[1], a[2], a[3], a[4], a[5]),
+                }
+            }
+            Value::Map(_) => {
+                return Err(SceneError::InvalidValue(
+                    item.line,
+                    "expected a transform".to_string(),
+                ))
+            }
+        };
+    }
+    Ok(transform)
+}
+
+fn find<'a>(entries: &'a [Entry], key: &str) -> Option<&'a Node> {
+    entries
+        .iter()
+        .find(|entry| entry.key == key)
+        .map(|entry| &entry.value)
+}
+
+fn required<T>(value: Option<T>, item: &Node, key: &str) -> Result<T, SceneError> {
+    value.ok_or_else(|| SceneError::MissingKey(item.line, key.to_string()))
+}
+
+/// A YAML value, with the line it starts on.
+#[derive(Debug, Clone, PartialEq)]
+struct Node {
+    line: usize,
+    value: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Scalar(String),
+    List(Vec<Node>),
+    Map(Vec<Entry>),
+}
+
+/// A key of a YAML mapping, with the line it is on, and its value.
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    key: String,
+    line: usize,
+    value: Node,
+}
+
+impl Entry {
+    fn unknown(&self) -> SceneError {
+        SceneError::UnknownKey(self.line, self.key.clone())
+    }
+}
+
+impl Node {
+    fn invalid(&self, expected: &str) -> SceneError {
+        SceneError::InvalidValue(self.line, format!("expected {}", expected))
+    }
+
+    fn scalar(&self) -> Result<&str, SceneError> {
+        match &self.value {
+            Value::Scalar(scalar) => Ok(scalar),
+            _ => Err(self.invalid("a single value")),
+        }
+    }
+
+    fn list(&self) -> Result<&[Node], SceneError> {
+        match &self.value {
+            Value::List(items) => Ok(items),
+            _ => Err(self.invalid("a list")),
+        }
+    }
+
+    fn map(&self) -> Result<&[Entry], SceneError> {
+        match &self.value {
+            Value::Map(entries) => Ok(entries),
+            _ => Err(self.invalid("a mapping")),
+        }
+    }
+
+    fn number(&self) -> Result<f64, SceneError> {
+        let scalar = self.scalar()?;
+        scalar.parse().map_err(|_| {
+            SceneError::InvalidValue(self.line, format!("expected a number, found {:?}", scalar))
+        })
+    }
+
+    fn size(&self) -> Result<usize, SceneError> {
+        self.scalar()?
+            .parse()
+            .map_err(|_| self.invalid("a positive integer"))
+    }
+
+    fn boolean(&self) -> Result<bool, SceneError> {
+        match self.scalar()? {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(self.invalid("true or false")),
+        }
+    }
+
+    fn triple(&self) -> Result<[f64; 3], SceneError> {
+        match self.list()? {
+            [x, y, z] => Ok([x.number()?, y.number()?, z.number()?]),
+            _ => Err(self.invalid("a list of 3 numbers")),
+        }
+    }
+
+    fn point(&self) -> Result<Point, SceneError> {
+        let [x, y, z] = self.triple()?;
+        Ok(Point { x, y, z })
+    }
+
+    fn vector(&self) -> Result<Vector, SceneError> {
+        let [x, y, z] = self.triple()?;
+        Ok(Vector { x, y, z })
+    }
+
+    fn color(&self) -> Result<Color, SceneError> {
+        let [red, green, blue] = self.triple()?;
+        Ok(Color { red, green, blue })
+    }
+}
+
+/// A non-blank line, stripped of its indentation and comment.
+struct Line<'a> {
+    number: usize,
+    indent: usize,
+    content: &'a str,
+}
+
+/// Parses the subset of YAML used by scene files: block lists and
+/// mappings nested by indentation, single line flow lists such as
+/// `[translate, 1, 2, 3]`, plain or quoted scalars, and comments.
+/// Returns `None` when there is nothing but comments and blank lines.
+fn parse_yaml(source: &str) -> Result<Option<Node>, SceneError> {
+    let mut lines = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let line = strip_comment(line).trim_end();
+        let content = line.trim_start_matches(' ');
+        if content.starts_with('\t') {
+            return Err(SceneError::Syntax(
+                index + 1,
+                "tabs can't be used for indentation".to_string(),
+            ));
+        }
+        if content.is_empty() || content == "---" {
+            continue;
+        }
+        lines.push(Line {
+            number: index + 1,
+            indent: line.len() - content.len(),
+            content,
+        });
+    }
+    let mut parser = Parser { lines, position: 0 };
+    let indent = match parser.lines.first() {
+        Some(line) => line.indent,
+        None => return Ok(None),
+    };
+    let root = parser.parse_block(indent)?;
+    match parser.lines.get(parser.position) {
+        Some(line) => Err(SceneError::Syntax(
+            line.number,
+            "unexpected indentation".to_string(),
+        )),
+        None => Ok(Some(root)),
+    }
+}
+
+/// Removes a comment, started by a `#` at the start of the line
+/// or after a space.
+fn strip_comment(line: &str) -> &str {
+    let bytes = line.as_bytes();
+    match (0..bytes.len()).find(|&i| bytes[i] == b'#' && (i == 0 || bytes[i - 1] == b' ')) {
+        Some(index) => &line[..index],
+        None => line,
+    }
+}
+
+fn is_item(content: &str) -> bool {
+    content == "-" || content.starts_with("- ")
+}
+
+/// Splits `key: value` into its key and value, the value
+/// being empty when it starts on the next line.
+fn split_key(content: &str) -> Option<(&str, &str)> {
+    if content.starts_with('[') || content.starts_with('"') || content.starts_with('\'') {
+        return None;
+    }
+    match content.find(": ") {
+        Some(index) => Some((content[..index].trim_end(), content[index + 2..].trim())),
+        None => content.strip_suffix(':').map(|key| (key.trim_end(), "")),
+    }
+}
+
+struct Parser<'a> {
+    lines: Vec<Line<'a>>,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn parse_block(&mut self, indent: usize) -> Result<Node, SceneError> {
+        if is_item(self.lines[self.position].content) {
+            self.parse_list(indent)
+        } else {
+            self.parse_map(indent)
+        }
+    }
+
+    fn parse_list(&mut self, indent: usize) -> Result<Node, SceneError> {
+        let start = self.lines[self.position].number;
+        let mut items = Vec::new();
+        while let Some(line) = self.lines.get(self.position) {
+            if line.indent < indent || !is_item(line.content) {
+                break;
+            }
+            if line.indent > indent {
+                return Err(SceneError::Syntax(
+                    line.number,
+                    "unexpected indentation".to_string(),
+                ));
+            }
+            let number = line.number;
+            let rest = &line.content[1..];
+            let item = rest.trim_start();
+            if item.is_empty() {
+                self.position += 1;
+                items.push(self.parse_nested(indent + 1, number)?);
+            } else if split_key(item).is_some() {
+                // the mapping continues on the next lines, aligned with its first key
+                let indent = indent + line.content.len() - item.len();
+                self.lines[self.position].indent = indent;
+                self.lines[self.position].content = item;
+                items.push(self.parse_map(indent)?);
+            } else {
+                self.position += 1;
+                items.push(parse_inline(item, number)?);
+            }
+        }
+        Ok(Node {
+            line: start,
+            value: Value::List(items),
+        })
+    }
+
+    fn parse_map(&mut self, indent: usize) -> Result<Node, SceneError> {
+        let start = self.lines[self.position].number;
+        let mut entries: Vec<Entry> = Vec::new();
+        while let Some(line) = self.lines.get(self.position) {
+            if line.indent < indent || (line.indent == indent && is_item(line.content)) {
+                break;
+            }
+            if line.indent > indent {
+                return Err(SceneError::Syntax(
+                    line.number,
+                    "unexpected indentation".to_string(),
+                ));
+            }
+            let number = line.number;
+            let (key, rest) = split_key(line.content)
+                .ok_or_else(|| SceneError::Syntax(number, "expected `key: value`".to_string()))?;
+            if entries.iter().any(|entry| entry.key == key) {
+                return Err(SceneError::Syntax(
+                    number,
+                    format!("duplicate key {:?}", key),
+                ));
+            }
+            self.position += 1;
+            let value = if rest.is_empty() {
+                // a list may start at the same indentation as its key
+                match self.lines.get(self.position) {
+                    Some(next) if next.indent == indent && is_item(next.content) => {
+                        self.parse_list(indent)?
+                    }
+                    _ => self.parse_nested(indent + 1, number)?,
+                }
+            } else {
+                parse_inline(rest, number)?
+            };
+            entries.push(Entry {
+                key: unquote(key).to_string(),
+                line: number,
+                value,
+            });
+        }
+        Ok(Node {
+            line: start,
+            value: Value::Map(entries),
+        })
+    }
+
+    /// Parses the block starting on the next line, indented
+    /// by at least `indent`, for a key or item on line `number`.
+    fn parse_nested(&mut self, indent: usize, number: usize) -> Result<Node, SceneError> {
+        match self.lines.get(self.position) {
+            Some(line) if line.indent >= indent => self.parse_block(line.indent),
+            _ => Err(SceneError::Syntax(number, "missing value".to_string())),
+        }
+    }
+}
+
+fn parse_inline(text: &str, line: usize) -> Result<Node, SceneError> {
+    if text.starts_with('{') {
+        return Err(SceneError::Syntax(
+            line,
+            "flow mappings are not supported".to_string(),
+        ));
+    }
+    if !text.starts_with('[') {
+        return Ok(Node {
+            line,
+            value: Value::Scalar(unquote(text).to_string()),
+        });
+    }
+    let (node, rest) = parse_flow_list(text, line)?;
+    if !rest.trim().is_empty() {
+        return Err(SceneError::Syntax(
+            line,
+            format!("unexpected {:?} after list", rest.trim()),
+        ));
+    }
+    Ok(node)
+}
+
+/// Parses the flow list `text` starts with, possibly nested,
+/// and returns it with the text that follows it.
+fn parse_flow_list(text: &str, line: usize) -> Result<(Node, &str), SceneError> {
+    let unclosed = || SceneError::Syntax(line, "unclosed `[`".to_string());
+    let mut items = Vec::new();
+    let mut rest = text[1..].trim_start();
+    if let Some(rest) = rest.strip_prefix(']') {
+        let list = Node {
+            line,
+            value: Value::List(items),
+        };
+        return Ok((list, rest));
+    }
+    loop {
+        let (item, after) = if rest.starts_with('[') {
+            parse_flow_list(rest, line)?
+        } else {
+            let end = rest.find([',', ']']).ok_or_else(unclosed)?;
+            let scalar = Node {
+                line,
+                value: Value::Scalar(unquote(rest[..end].trim()).to_string()),
+            };
+            (scalar, &rest[end..])
+        };
+        items.push(item);
+        let after = after.trim_start();
+        if let Some(after) = after.strip_prefix(',') {
+            rest = after.trim_start();
+        } else if let Some(after) = after.strip_prefix(']') {
+            let list = Node {
+                line,
+                value: Value::List(items),
+            };
+            return Ok((list, after));
+        } else {
+            return Err(unclosed());
+        }
+    }
+}
+
+fn unquote(scalar: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = scalar
+            .strip_prefix(quote)
+            .and_then(|scalar| scalar.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    scalar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::Projection;
+    use crate::{color, point, vector};
+    use std::f64::consts::PI;
+
+    fn scalar(line: usize, value: &str) -> Node {
+        Node {
+            line,
+            value: Value::Scalar(value.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_yaml() {
+        // Nested lists and mappings, with comments and flow lists
+        let node = parse_yaml(
+            "# a scene\n\
+             - add: camera\n\
+             \x20 from: [ -6, 6, -10 ] # eye\n\
+             \n\
+             - define: t\n\
+             \x20 value:\n\
+             \x20   - [ scale, 0.5, 0.5, 0.5 ]\n\
+             \x20   - other\n",
+        )
+        .unwrap()
+        .unwrap();
+        let items = node.list().unwrap();
+        assert_eq!(items.len(), 2);
+        let camera = items[0].map().unwrap();
+        assert_eq!(camera[0].key, "add");
+        assert_eq!(camera[0].value, scalar(2, "camera"));
+        assert_eq!(camera[1].line, 3);
+        assert_eq!(
+            camera[1].value.list().unwrap(),
+            &[scalar(3, "-6"), scalar(3, "6"), scalar(3, "-10")]
+        );
+        let value = find(items[1].map().unwrap(), "value").unwrap();
+        assert_eq!(value.line, 7);
+        assert_eq!(value.list().unwrap()[0].list().unwrap().len(), 4);
+        assert_eq!(value.list().unwrap()[1], scalar(8, "other"));
+
+        // A list may start at the same indentation as its key
+        let node = parse_yaml("transform:\n- [rotate-x, 1]\n")
+            .unwrap()
+            .unwrap();
+        assert_eq!(node.map().unwrap()[0].value.list().unwrap().len(), 1);
+
+        // Blank scenes have no root
+        assert_eq!(parse_yaml("# nothing\n\n").unwrap(), None);
+
+        // Malformed lines are reported
+        let cases = [
+            ("- [translate, 1, 2\n", 1),
+            ("- add: light\n    at: [0, 0, 0]\n", 2),
+            ("a: 1\nb:\n", 2),
+            ("a: 1\na: 2\n", 2),
+            ("a: 1\njust text\n", 2),
+        ];
+        for (source, line) in cases {
+            match parse_yaml(source) {
+                Err(SceneError::Syntax(result, _)) => assert_eq!(result, line),
+                result => panic!("expected a syntax error, got {:?}", result),
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_scene() {
+        // Definitions, extended materials and combined transforms
+        let (camera, world) = load_scene(
+            "
+- add: camera
+  width: 40
+  height: 20
+  field-of-view: 0.785
+  from: [ 0, 0, -5 ]
+  to: [ 0, 0, 0 ]
+  up: [ 0, 1, 0 ]
+
+- add: light
+  at: [ 50, 100, -50 ]
+  intensity: [ 1, 1, 1 ]
+
+- define: white-material
+  value:
+    color: [ 1, 1, 1 ]
+    diffuse: 0.7
+    ambient: 0.1
+    specular: 0.0
+    reflective: 0.1
+
+- define: blue-material
+  extend: white-material
+  value:
+    color: [ 0.537, 0.831, 0.914 ]
+
+- define: standard-transform
+  value:
+    - [ translate, 1, -1, 1 ]
+    - [ scale, 0.5, 0.5, 0.5 ]
+
+- define: large-object
+  value:
+    - standard-transform
+    - [ scale, 3.5, 3.5, 3.5 ]
+
+- add: plane
+  shadow: false
+  material:
+    color: [ 1, 1, 1 ]
+    ambient: 1
+  transform:
+    - [ rotate-x, 1.5707963267948966 ] # pi/2
+    - [ translate, 0, 0, 500 ]
+
+- add: cube
+  material: blue-material
+  transform:
+    - large-object
+    - [ translate, 8.5, 1.5, -0.5 ]
+",
+        )
+        .unwrap();
+        assert_eq!(camera.hsize, 40);
+        assert_eq!(camera.vsize, 20);
+        assert_eq!(
+            camera.projection,
+            Projection::Perspective {
+                field_of_view: 0.785
+            }
+        );
+        assert_eq!(
+            camera.transform,
+            Mat4::identity().view_transform(point![0, 0, -5], point![0, 0, 0], vector![0, 1, 0])
+        );
+        assert_eq!(
+            world.lights,
+            vec![Light::point(point![50, 100, -50], color![1, 1, 1])]
+        );
+        assert_eq!(world.objects.len(), 2);
+        let plane = &world.objects[0];
+        assert_eq!(
+            plane.transform(),
+            &Mat4::identity().rotate_x(PI / 2.0).translate(0, 0, 500)
+        );
+        assert_eq!(plane.material().ambient, 1.0);
+        assert!(!plane.material().casts_shadow);
+        let cube = &world.objects[1];
+        assert_eq!(
+            cube.transform(),
+            &Mat4::identity()
+                .translate(1, -1, 1)
+                .uniform_scale(0.5)
+                .uniform_scale(3.5)
+                .translate(8.5, 1.5, -0.5)
+        );
+        let mut material = Material::new();
+        material.color = color![0.537, 0.831, 0.914];
+        material.diffuse = 0.7;
+        material.specular = 0.0;
+        assert_eq!(cube.material(), &material);
+        assert_eq!(camera.render(&world).width, 40);
+    }
+
+    #[test]
+    fn test_load_scene_errors() {
+        let camera = "- add: camera\n  width: 10\n  height: 10\n  field-of-view: 1\n  \
+                      from: [0, 0, -5]\n  to: [0, 0, 0]\n  up: [0, 1, 0]\n";
+        let cases = [
+            (
+                "- add: sphere\n  material:\n    colour: [1, 0, 0]\n",
+                SceneError::UnknownKey(3, "colour".to_string()),
+            ),
+            (
+                "- add: torus\n",
+                SceneError::InvalidValue(1, "unknown element \"torus\"".to_string()),
+            ),
+            (
+                "- add: cube\n  transform:\n    - [translate, 1, 2]\n",
+                SceneError::InvalidValue(3, "\"translate\" takes 3 arguments, found 2".to_string()),
+            ),
+            (
+                "- add: cube\n  material: shiny\n",
+                SceneError::Undefined(2, "shiny".to_string()),
+            ),
+            (
+                "- add: light\n  at: [0, 0, 0]\n",
+                SceneError::MissingKey(1, "intensity".to_string()),
+            ),
+            (
+                "- add: light\n  at: [0, zero, 0]\n",
+                SceneError::InvalidValue(2, "expected a number, found \"zero\"".to_string()),
+            ),
+            (
+                "- camera: yes\n",
+                SceneError::MissingKey(1, "add".to_string()),
+            ),
+        ];
+        for (source, error) in cases {
+            let source = format!("{}{}", source, camera);
+            assert_eq!(load_scene(&source).err(), Some(error));
+        }
+
+        // A scene needs a camera
+        assert_eq!(
+            load_scene("- add: sphere\n").err(),
+            Some(SceneError::MissingCamera)
+        );
+        assert_eq!(
+            SceneError::UnknownKey(3, "colour".to_string()).to_string(),
+            "line 3: unknown key \"colour\""
+        );
+    }
+}
@@ -1,39 +1,360 @@
+use crate::bounds::BoundingBox;
 use crate::intersection::Intersection;
 use crate::mat4::Mat4;
 use crate::material::Material;
+use crate::pattern::Pattern;
 use crate::point::Point;
 use crate::ray::Ray;
+use crate::stats;
 use crate::vector::Vector;
+#[cfg(feature = "serde")]
+use crate::world::SerializableShape;
 use std::fmt::Debug;
 
-pub trait Shape {
-    fn intersect(&self, ray: Ray) -> Vec<Intersection> {
-        let local_ray = ray.transform(self.transform().inverse());
-        self.local_intersect(local_ray)
+/// Shapes are `Send` and `Sync` so worlds can be rendered from several threads.
+pub trait Shape: Send + Sync {
+    fn intersect(&self, ray: Ray) -> Vec<Intersection<'_>> {
+        let mut intersections = Vec::new();
+        self.intersect_into(ray, &mut intersections);
+        intersections
+    }
+
+    /// Same as `intersect`, pushing the intersections onto
+    /// `intersections` instead of allocating a new vector, so
+    /// a single buffer can be reused across objects and rays.
+    fn intersect_into<'s>(&'s self, ray: Ray, intersections: &mut Vec<Intersection<'s>>) {
+        stats::count(|stats| stats.intersection_tests += 1);
+        // a singular transform leaves no inverse to bring the ray into object space
+        if !self.transforms().is_invertible() {
+            return;
+        }
+        let local_ray = ray.transform(*self.transforms().inverse());
+        self.local_intersect_into(local_ray, intersections);
     }
 
     fn normal_at(&self, point: Point) -> Vector {
-        let local_point = self.transform().inverse() * point;
+        let local_point = self.world_to_object(point);
         let local_normal = self.local_normal_at(local_point);
-        let world_normal = self.transform().inverse().transpose() * local_normal;
-        world_normal.normalize()
+        self.normal_to_world(local_normal)
+    }
+
+    /// Same as `normal_at`, for shapes whose normal depends on where
+    /// the hit lies relative to their edges, given by `u` and `v`.
+    fn normal_at_uv(&self, point: Point, u: f64, v: f64) -> Vector {
+        let local_point = self.world_to_object(point);
+        let local_normal = self.local_normal_at_uv(local_point, u, v);
+        self.normal_to_world(local_normal)
+    }
+
+    /// Same as `normal_at`, given the intersection `point` lies on,
+    /// for shapes needing more than the point, such as the `u` and `v`
+    /// of smooth triangles. The default ignores the intersection.
+    fn normal_at_hit(&self, point: Point, _hit: &Intersection) -> Vector {
+        self.normal_at(point)
+    }
+
+    /// Converts a point from world space to object space,
+    /// through the transforms of the parent groups, if any.
+    fn world_to_object(&self, point: Point) -> Point {
+        self.transforms().world_inverse() * point
+    }
+
+    /// Converts a normal from object space to world space,
+    /// through the transforms of the parent groups, if any.
+    fn normal_to_world(&self, normal: Vector) -> Vector {
+        (self.transforms().world_inverse_transpose() * normal).normalize()
+    }
+
+    fn transform(&self) -> &Mat4 {
+        self.transforms().transform()
+    }
+
+    /// Combined transform of every group containing the shape,
+    /// identity for shapes outside of any group.
+    fn parent_transform(&self) -> &Mat4 {
+        self.transforms().parent()
+    }
+
+    /// Whether `other` is this shape, or one of its descendants
+    /// for shapes made of other shapes.
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.id() == other.id()
+    }
+
+    fn local_normal_at_uv(&self, local_point: Point, _u: f64, _v: f64) -> Vector {
+        self.local_normal_at(local_point)
+    }
+
+    /// Bounds of the shape once transformed,
+    /// in the space of its parent group.
+    fn parent_space_bounds(&self) -> BoundingBox {
+        self.bounds().transform(self.transform())
     }
 
-    fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection>;
+    /// Same as `local_intersect`, pushing onto `intersections`.
+    /// The default collects `local_intersect`, shapes override
+    /// it to skip the intermediate vector.
+    fn local_intersect_into<'s>(
+        &'s self,
+        local_ray: Ray,
+        intersections: &mut Vec<Intersection<'s>>,
+    ) {
+        intersections.extend(self.local_intersect(local_ray));
+    }
+
+    fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection<'_>>;
+    /// Bounds of the shape in object space.
+    fn bounds(&self) -> BoundingBox;
     fn local_normal_at(&self, local_point: Point) -> Vector;
-    fn transform(&self) -> &Mat4;
+    fn transforms(&self) -> &Transforms;
+    fn set_parent_transform(&mut self, transform: Mat4);
+    /// Returns the shape tagged with its type, for serializing
+    /// worlds, `None` for shapes that can't be serialized.
+    #[cfg(feature = "serde")]
+    fn serializable(&self) -> Option<SerializableShape<'_>> {
+        None
+    }
+    /// Name given to the shape with `ShapeBuilder::set_label`, to tell
+    /// shapes apart when debugging.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// # use trace::{intersection::Intersection, ray::Ray};
+    /// let mut world = World::default();
+    /// world.push(
+    ///     Cube::new()
+    ///         .set_transform(Mat4::identity().translate(5, 0, 0))
+    ///         .set_label("crate"),
+    /// );
+    /// let ray = Ray {
+    ///     origin: point![5, 0, 5],
+    ///     direction: vector![0, 0, -1],
+    /// };
+    /// let intersections = world.intersect(ray);
+    /// let hit = Intersection::hit(&intersections).unwrap();
+    /// assert_eq!(hit.object.label(), Some("crate"));
+    /// assert!(hit.object.debug().contains("label: Some(\"crate\")"));
+    /// assert_eq!(world.objects[0].label(), None);
+    /// ```
+    fn label(&self) -> Option<&str> {
+        None
+    }
+    /// Storage of the label, `None` for shapes that can't be
+    /// labelled, which `ShapeBuilder::set_label` leaves as is.
+    fn label_mut(&mut self) -> Option<&mut Option<String>> {
+        None
+    }
     fn material(&self) -> &Material;
     fn material_mut(&mut self) -> &mut Material;
+    /// Returns a boxed copy of the shape, keeping its id, so
+    /// the copy is still found by the id of the original, e.g.
+    /// in a cloned `World`. A copy added to the same world as
+    /// its original is reported by `World::validate`.
+    fn clone_box(&self) -> Box<dyn Shape>;
     fn debug(&self) -> String;
     fn id(&self) -> usize;
 }
 
+/// Builders shared by every shape, configuring it in a single expression.
+/// Transforms are set with each shape's own `set_transform`, as groups
+/// and CSG shapes pass theirs on to their children.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// let cube = Cube::new()
+///     .set_transform(Mat4::identity().uniform_scale(2))
+///     .set_material(Material::new().with_ambient(0.3))
+///     .set_pattern(Box::new(Stripe::default()));
+/// assert_eq!(cube.material().ambient, 0.3);
+/// assert!(cube.material().pattern.is_some());
+/// ```
+pub trait ShapeBuilder: Shape + Sized {
+    fn set_material(mut self, material: Material) -> Self {
+        *self.material_mut() = material;
+
+        self
+    }
+
+    fn set_pattern(mut self, pattern: Box<dyn Pattern>) -> Self {
+        self.material_mut().pattern = Some(pattern);
+
+        self
+    }
+
+    /// Names the shape, as shown by its debug output.
+    fn set_label(mut self, label: &str) -> Self {
+        if let Some(storage) = self.label_mut() {
+            *storage = Some(label.to_string());
+        }
+
+        self
+    }
+}
+
+impl<T: Shape> ShapeBuilder for T {}
+
+/// The transform of a shape and the parent transform given by
+/// the groups containing it, along with their inverses, which are
+/// recomputed only when either transform changes rather than for
+/// every ray.
+///
+/// A singular transform, or one containing NaN, has no inverse, its
+/// inverses are then filled with NaN, `Shape::intersect` skips the
+/// shape, and it is reported by `World::validate`.
+///
+/// # Examples
+///
+/// ```
+/// # use trace::prelude::*;
+/// # use trace::shape::Transforms;
+/// let mut transforms = Transforms::default();
+/// transforms.set_transform(Mat4::identity().uniform_scale(2));
+/// transforms.set_parent(Mat4::identity().translate(1, 0, 0));
+/// assert_eq!(transforms.inverse(), &Mat4::identity().uniform_scale(0.5));
+/// assert_eq!(transforms.world_inverse() * point![3, 2, 2], point![1, 1, 1]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "TransformsData", into = "TransformsData")
+)]
+pub struct Transforms {
+    transform: Mat4,
+    parent: Mat4,
+    inverse: Mat4,
+    world_inverse: Mat4,
+    world_inverse_transpose: Mat4,
+    invertible: bool,
+}
+
+impl Transforms {
+    pub fn transform(&self) -> &Mat4 {
+        &self.transform
+    }
+
+    pub fn parent(&self) -> &Mat4 {
+        &self.parent
+    }
+
+    /// Inverse of the transform, from parent space to object space.
+    pub fn inverse(&self) -> &Mat4 {
+        &self.inverse
+    }
+
+    /// Whether the transform has an inverse, see `Mat4::is_invertible`.
+    pub fn is_invertible(&self) -> bool {
+        self.invertible
+    }
+
+    /// Inverse of the parent transform times the transform,
+    /// from world space to object space.
+    pub fn world_inverse(&self) -> &Mat4 {
+        &self.world_inverse
+    }
+
+    /// Transpose of `world_inverse`, taking normals
+    /// from object space to world space.
+    pub fn world_inverse_transpose(&self) -> &Mat4 {
+        &self.world_inverse_transpose
+    }
+
+    /// Parent transform times the transform,
+    /// from object space to world space.
+    pub fn world(&self) -> Mat4 {
+        self.parent * self.transform
+    }
+
+    pub fn set_transform(&mut self, transform: Mat4) {
+        self.invertible = transform.is_invertible();
+        self.inverse = inverse_or_nan(&transform);
+        self.transform = transform;
+        self.update_world();
+    }
+
+    pub fn set_parent(&mut self, parent: Mat4) {
+        self.parent = parent;
+        self.update_world();
+    }
+
+    fn update_world(&mut self) {
+        self.world_inverse = inverse_or_nan(&self.world());
+        self.world_inverse_transpose = self.world_inverse.transpose();
+    }
+}
+
+impl Default for Transforms {
+    fn default() -> Self {
+        Self {
+            transform: Mat4::identity(),
+            parent: Mat4::identity(),
+            inverse: Mat4::identity(),
+            world_inverse: Mat4::identity(),
+            world_inverse_transpose: Mat4::identity(),
+            invertible: true,
+        }
+    }
+}
+
+/// `Transforms` as serialized, without the inverses,
+/// which are recomputed when deserializing.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TransformsData {
+    transform: Mat4,
+    parent: Mat4,
+}
+
+#[cfg(feature = "serde")]
+impl From<TransformsData> for Transforms {
+    fn from(data: TransformsData) -> Self {
+        let mut transforms = Self::default();
+        transforms.set_transform(data.transform);
+        transforms.set_parent(data.parent);
+        transforms
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Transforms> for TransformsData {
+    fn from(transforms: Transforms) -> Self {
+        Self {
+            transform: transforms.transform,
+            parent: transforms.parent,
+        }
+    }
+}
+
+/// Returns a new unique shape id.
+#[cfg(feature = "serde")]
+pub(crate) fn next_id() -> usize {
+    crate::prelude::OBJECT_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+}
+
+fn inverse_or_nan(mat: &Mat4) -> Mat4 {
+    if mat.is_invertible() {
+        mat.inverse()
+    } else {
+        Mat4::from(&[f64::NAN; 16][..])
+    }
+}
+
 impl Debug for dyn Shape {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.debug())
     }
 }
 
+impl Clone for Box<dyn Shape> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
 impl PartialEq for dyn Shape {
     fn eq(&self, rhs: &Self) -> bool {
         self.id() == rhs.id()
@@ -1,23 +1,28 @@
+use crate::bounds::BoundingBox;
 use crate::intersection::Intersection;
 use crate::mat4::Mat4;
 use crate::material::Material;
-use crate::pattern::Pattern;
 use crate::point;
 use crate::point::Point;
 use crate::prelude::is_equal;
 use crate::prelude::OBJECT_COUNTER;
 use crate::ray::Ray;
-use crate::shape::Shape;
+use crate::shape::{Shape, Transforms};
 use crate::vector::Vector;
 use std::sync::atomic::Ordering;
 
 /// `Sphere` instances are situated at the world's origin (0, 0, 0),
 /// and are all unit spheres, with radius of 1.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sphere {
+    /// Deserialized shapes are given a new id.
+    #[cfg_attr(feature = "serde", serde(skip, default = "crate::shape::next_id"))]
     pub id: usize,
-    pub transform: Mat4,
+    transforms: Transforms,
     pub material: Material,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub label: Option<String>,
 }
 
 impl Sphere {
@@ -26,30 +31,52 @@ impl Sphere {
     /// ```
     /// # use trace::prelude::*;
     /// // A sphere's default transformation
-    /// let mut sphere = Sphere::new();
-    /// assert_eq!(sphere.transform, Mat4::identity());
+    /// let sphere = Sphere::new();
+    /// assert_eq!(sphere.transform(), &Mat4::identity());
     ///
     /// // Changing a sphere's transformation
-    /// let mut sphere = Sphere::new();
-    /// sphere.transform = Mat4::identity().translate(2, 3, 4);
-    /// assert_eq!(sphere.transform, Mat4::identity().translate(2, 3, 4));
+    /// let sphere = Sphere::new().set_transform(Mat4::identity().translate(2, 3, 4));
+    /// assert_eq!(sphere.transform(), &Mat4::identity().translate(2, 3, 4));
     /// ```
     pub fn new() -> Self {
         Self {
             id: OBJECT_COUNTER.fetch_add(1, Ordering::Relaxed),
-            transform: Mat4::identity(),
+            transforms: Transforms::default(),
             material: Material::new(),
+            label: None,
         }
     }
 
-    pub fn set_transform(mut self, transform: Mat4) -> Self {
-        self.transform = transform;
+    /// Sphere of radius `radius` centered at the origin,
+    /// a unit sphere scaled by `radius`.
+    pub fn with_radius(radius: f64) -> Self {
+        Self::new().set_transform(Mat4::identity().uniform_scale(radius))
+    }
 
-        self
+    /// Sphere of radius `radius` centered at `center`, a unit
+    /// sphere scaled by `radius` then translated to `center`.
+    /// Like any transform, it is replaced by `set_transform`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let sphere = Sphere::at(point![1, 2, 3], 0.5);
+    /// assert_eq!(
+    ///     sphere.transform(),
+    ///     &Mat4::identity().uniform_scale(0.5).translate(1, 2, 3)
+    /// );
+    /// ```
+    pub fn at(center: Point, radius: f64) -> Self {
+        Self::new().set_transform(
+            Mat4::identity()
+                .uniform_scale(radius)
+                .translate(center.x, center.y, center.z),
+        )
     }
 
-    pub fn set_pattern(mut self, pattern: Box<dyn Pattern>) -> Self {
-        self.material.pattern = Some(pattern);
+    pub fn set_transform(mut self, transform: Mat4) -> Self {
+        self.transforms.set_transform(transform);
 
         self
     }
@@ -59,14 +86,25 @@ impl Default for Sphere {
     fn default() -> Self {
         Self {
             id: OBJECT_COUNTER.fetch_add(1, Ordering::Relaxed),
-            transform: Mat4::identity(),
+            transforms: Transforms::default(),
             material: Material::new(),
+            label: None,
         }
     }
 }
 
 impl Shape for Sphere {
-    fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection> {
+    fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection<'_>> {
+        let mut intersections = Vec::new();
+        self.local_intersect_into(local_ray, &mut intersections);
+        intersections
+    }
+
+    fn local_intersect_into<'s>(
+        &'s self,
+        local_ray: Ray,
+        intersections: &mut Vec<Intersection<'s>>,
+    ) {
         // the vector from the sphere's center, to the ray origin
         // remember: the sphere is centered at the world origin
         let sphere_to_ray = local_ray.origin - point![0, 0, 0];
@@ -75,23 +113,23 @@ impl Shape for Sphere {
         let c = sphere_to_ray.dot(sphere_to_ray) - 1.0;
         let discriminant = b.powi(2) - 4.0 * a * c;
         if discriminant < 0.0 {
-            Vec::new()
-        } else if is_equal(discriminant, 0.0) {
-            vec![Intersection {
-                t: (-b - discriminant.sqrt()) / (2.0 * a),
-                object: self,
-            }]
+            return;
+        }
+        if is_equal(discriminant, 0.0) {
+            // a tangent ray touches the sphere twice at the same point,
+            // so every ray entering a sphere also leaves it
+            let t = -b / (2.0 * a);
+            intersections.push(Intersection::new(t, self));
+            intersections.push(Intersection::new(t, self));
         } else {
-            vec![
-                Intersection {
-                    t: (-b - discriminant.sqrt()) / (2.0 * a),
-                    object: self,
-                },
-                Intersection {
-                    t: (-b + discriminant.sqrt()) / (2.0 * a),
-                    object: self,
-                },
-            ]
+            intersections.push(Intersection::new(
+                (-b - discriminant.sqrt()) / (2.0 * a),
+                self,
+            ));
+            intersections.push(Intersection::new(
+                (-b + discriminant.sqrt()) / (2.0 * a),
+                self,
+            ));
         }
     }
 
@@ -101,8 +139,29 @@ impl Shape for Sphere {
         local_point - Point::zero()
     }
 
-    fn transform(&self) -> &Mat4 {
-        &self.transform
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(point![-1, -1, -1], point![1, 1, 1])
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn set_parent_transform(&mut self, transform: Mat4) {
+        self.transforms.set_parent(transform);
+    }
+
+    #[cfg(feature = "serde")]
+    fn serializable(&self) -> Option<crate::world::SerializableShape<'_>> {
+        Some(crate::world::SerializableShape::Sphere(self))
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn label_mut(&mut self) -> Option<&mut Option<String>> {
+        Some(&mut self.label)
     }
 
     fn material(&self) -> &Material {
@@ -113,6 +172,10 @@ impl Shape for Sphere {
         &mut self.material
     }
 
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
     fn debug(&self) -> String {
         format!("{:?}", self)
     }
@@ -126,6 +189,7 @@ impl Shape for Sphere {
 mod tests {
     use super::*;
     use crate::vector;
+    use std::f64::consts::FRAC_1_SQRT_2;
     use std::f64::consts::PI;
     use std::f64::consts::SQRT_2;
 
@@ -149,8 +213,9 @@ mod tests {
         };
         let sphere = Sphere::new();
         let intersections = sphere.intersect(ray);
-        assert_eq!(intersections.len(), 1);
+        assert_eq!(intersections.len(), 2);
         assert!(is_equal(intersections[0].t, 5.0));
+        assert!(is_equal(intersections[1].t, 5.0));
 
         // A ray misses a sphere
         let ray = Ray {
@@ -200,7 +265,7 @@ mod tests {
             direction: vector![0, 0, 1],
         };
         let mut sphere = Sphere::new();
-        sphere.transform = Mat4::identity().scale(2, 2, 2);
+        sphere = sphere.set_transform(Mat4::identity().uniform_scale(2));
         let intersections = sphere.intersect(ray);
         assert_eq!(intersections.len(), 2);
         assert!(is_equal(intersections[0].t, 3.0));
@@ -212,9 +277,63 @@ mod tests {
             direction: vector![0, 0, 1],
         };
         let mut sphere = Sphere::new();
-        sphere.transform = Mat4::identity().translate(5, 0, 0);
+        sphere = sphere.set_transform(Mat4::identity().translate(5, 0, 0));
         let intersections = sphere.intersect(ray);
         assert_eq!(intersections.len(), 0);
+
+        // A sphere with a singular transform is never hit
+        let sphere = Sphere::new().set_transform(Mat4::identity().scale(1, 1, 0));
+        assert!(sphere.intersect(ray).is_empty());
+
+        // A small sphere is still hit
+        let sphere = Sphere::with_radius(0.02);
+        let intersections = sphere.intersect(ray);
+        assert_eq!(intersections.len(), 2);
+        assert!(is_equal(intersections[0].t, 4.98));
+        assert!(is_equal(intersections[1].t, 5.02));
+    }
+
+    #[test]
+    fn test_at() {
+        // Spheres with a radius and center match the transformed unit sphere
+        let cases = [
+            (Sphere::with_radius(2.0), Mat4::identity().uniform_scale(2)),
+            (
+                Sphere::at(point![1, -2, 0.5], 1.5),
+                Mat4::identity().uniform_scale(1.5).translate(1, -2, 0.5),
+            ),
+        ];
+        for (sphere, transform) in cases {
+            let manual = Sphere::new().set_transform(transform);
+            for ray in [
+                Ray {
+                    origin: point![0, 0, -5],
+                    direction: vector![0, 0, 1],
+                },
+                Ray {
+                    origin: point![1, 1, -5],
+                    direction: vector![0.1, -0.3, 1].normalize(),
+                },
+            ] {
+                let intersections = sphere.intersect(ray);
+                let expected = manual.intersect(ray);
+                assert_eq!(intersections.len(), expected.len());
+                for (intersection, expected) in intersections.iter().zip(&expected) {
+                    assert!(is_equal(intersection.t, expected.t));
+                    let point = ray.position(intersection.t);
+                    assert_eq!(sphere.normal_at(point), manual.normal_at(point));
+                }
+            }
+        }
+
+        // A sphere of radius 2 at the origin is hit 2 units before it
+        let ray = Ray {
+            origin: point![0, 0, -5],
+            direction: vector![0, 0, 1],
+        };
+        let sphere = Sphere::with_radius(2.0);
+        let intersections = sphere.intersect(ray);
+        assert!(is_equal(intersections[0].t, 3.0));
     }
 
     #[test]
@@ -257,15 +376,15 @@ mod tests {
 
         // Computing the normal on a translated sphere
         let mut sphere = Sphere::new();
-        sphere.transform = Mat4::identity().translate(0, 1, 0);
+        sphere = sphere.set_transform(Mat4::identity().translate(0, 1, 0));
         assert_eq!(
-            sphere.normal_at(point![0, 1.70711, -0.70711]),
-            vector![0, 0.70711, -0.70711]
+            sphere.normal_at(point![0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2]),
+            vector![0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2]
         );
 
         // Computing the normal on a transformed sphere
         let mut sphere = Sphere::new();
-        sphere.transform = Mat4::identity().rotate_z(PI / 5.0).scale(1, 0.5, 1);
+        sphere = sphere.set_transform(Mat4::identity().rotate_z(PI / 5.0).scale(1, 0.5, 1));
         assert_eq!(
             sphere.normal_at(point![0, SQRT_2 / 2.0, -SQRT_2 / 2.0]),
             vector![0, 0.97014, -0.24254]
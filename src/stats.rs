@@ -0,0 +1,52 @@
+use std::cell::Cell;
+use std::ops::AddAssign;
+
+/// Work done while rendering, as returned by `Camera::render_stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderStats {
+    /// Rays traced from the camera, one per sample.
+    pub primary_rays: usize,
+    /// Rays traced toward lights to check for shadows.
+    pub shadow_rays: usize,
+    /// Rays intersected with a single shape, children
+    /// of groups skipped by their bounding box aren't counted.
+    pub intersection_tests: usize,
+}
+
+impl AddAssign for RenderStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.primary_rays += rhs.primary_rays;
+        self.shadow_rays += rhs.shadow_rays;
+        self.intersection_tests += rhs.intersection_tests;
+    }
+}
+
+thread_local! {
+    // counting per thread keeps concurrent renders apart,
+    // and costs no synchronization between the workers
+    static COUNTS: Cell<RenderStats> = Cell::new(RenderStats::default());
+}
+
+/// Updates the counts of the calling thread.
+pub(crate) fn count(update: impl FnOnce(&mut RenderStats)) {
+    COUNTS.with(|counts| {
+        let mut stats = counts.get();
+        update(&mut stats);
+        counts.set(stats);
+    });
+}
+
+/// Runs `f`, returning the work it did on the calling thread.
+pub(crate) fn measure<T>(f: impl FnOnce() -> T) -> (T, RenderStats) {
+    let before = COUNTS.with(Cell::get);
+    let result = f();
+    let after = COUNTS.with(Cell::get);
+    let stats = RenderStats {
+        primary_rays: after.primary_rays.wrapping_sub(before.primary_rays),
+        shadow_rays: after.shadow_rays.wrapping_sub(before.shadow_rays),
+        intersection_tests: after
+            .intersection_tests
+            .wrapping_sub(before.intersection_tests),
+    };
+    (result, stats)
+}
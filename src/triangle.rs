@@ -0,0 +1,427 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::Intersection;
+use crate::mat4::Mat4;
+use crate::material::Material;
+use crate::point::Point;
+use crate::prelude::EPSILON;
+use crate::prelude::OBJECT_COUNTER;
+use crate::ray::Ray;
+use crate::shape::{Shape, Transforms};
+use crate::vector::Vector;
+use std::sync::atomic::Ordering;
+
+/// `Triangle` instances are defined by three vertices.
+/// The edges and the normal are computed once on construction,
+/// since they are needed by every intersection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triangle {
+    pub id: usize,
+    transforms: Transforms,
+    pub material: Material,
+    pub label: Option<String>,
+    pub p1: Point,
+    pub p2: Point,
+    pub p3: Point,
+    pub e1: Vector,
+    pub e2: Vector,
+    pub normal: Vector,
+}
+
+impl Triangle {
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// // Constructing a triangle
+    /// let triangle = Triangle::new(point![0, 1, 0], point![-1, 0, 0], point![1, 0, 0]);
+    /// assert_eq!(triangle.e1, vector![-1, -1, 0]);
+    /// assert_eq!(triangle.e2, vector![1, -1, 0]);
+    /// assert_eq!(triangle.normal, vector![0, 0, -1]);
+    /// ```
+    pub fn new(p1: Point, p2: Point, p3: Point) -> Self {
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+        Self {
+            id: OBJECT_COUNTER.fetch_add(1, Ordering::Relaxed),
+            transforms: Transforms::default(),
+            material: Material::new(),
+            label: None,
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal: e2.cross(e1).normalize(),
+        }
+    }
+
+    pub fn set_transform(mut self, transform: Mat4) -> Self {
+        self.transforms.set_transform(transform);
+
+        self
+    }
+}
+
+/// Möller–Trumbore ray-triangle intersection, returns `t` along with
+/// the `u` and `v` barycentric coordinates of the hit, if any.
+fn intersect_triangle(p1: Point, e1: Vector, e2: Vector, ray: Ray) -> Option<(f64, f64, f64)> {
+    let dir_cross_e2 = ray.direction.cross(e2);
+    let det = e1.dot(dir_cross_e2);
+    // ray is parallel to the triangle
+    if det.abs() < EPSILON {
+        return None;
+    }
+    let f = 1.0 / det;
+    let p1_to_origin = ray.origin - p1;
+    let u = f * p1_to_origin.dot(dir_cross_e2);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let origin_cross_e1 = p1_to_origin.cross(e1);
+    let v = f * ray.direction.dot(origin_cross_e1);
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = f * e2.dot(origin_cross_e1);
+    Some((t, u, v))
+}
+
+impl Shape for Triangle {
+    fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection<'_>> {
+        let mut intersections = Vec::new();
+        self.local_intersect_into(local_ray, &mut intersections);
+        intersections
+    }
+
+    fn local_intersect_into<'s>(
+        &'s self,
+        local_ray: Ray,
+        intersections: &mut Vec<Intersection<'s>>,
+    ) {
+        if let Some((t, _, _)) = intersect_triangle(self.p1, self.e1, self.e2, local_ray) {
+            intersections.push(Intersection::new(t, self));
+        }
+    }
+
+    /// The normal of a triangle is constant everywhere.
+    fn local_normal_at(&self, _local_point: Point) -> Vector {
+        self.normal
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::empty();
+        bounds.add_point(self.p1);
+        bounds.add_point(self.p2);
+        bounds.add_point(self.p3);
+        bounds
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn set_parent_transform(&mut self, transform: Mat4) {
+        self.transforms.set_parent(transform);
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn label_mut(&mut self) -> Option<&mut Option<String>> {
+        Some(&mut self.label)
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    fn debug(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+}
+
+/// `SmoothTriangle` instances are triangles with a normal for each
+/// vertex, the normal at any point is interpolated between them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmoothTriangle {
+    pub id: usize,
+    transforms: Transforms,
+    pub material: Material,
+    pub label: Option<String>,
+    pub p1: Point,
+    pub p2: Point,
+    pub p3: Point,
+    pub n1: Vector,
+    pub n2: Vector,
+    pub n3: Vector,
+    pub e1: Vector,
+    pub e2: Vector,
+}
+
+impl SmoothTriangle {
+    pub fn new(p1: Point, p2: Point, p3: Point, n1: Vector, n2: Vector, n3: Vector) -> Self {
+        Self {
+            id: OBJECT_COUNTER.fetch_add(1, Ordering::Relaxed),
+            transforms: Transforms::default(),
+            material: Material::new(),
+            label: None,
+            p1,
+            p2,
+            p3,
+            n1,
+            n2,
+            n3,
+            e1: p2 - p1,
+            e2: p3 - p1,
+        }
+    }
+
+    pub fn set_transform(mut self, transform: Mat4) -> Self {
+        self.transforms.set_transform(transform);
+
+        self
+    }
+}
+
+impl Shape for SmoothTriangle {
+    fn local_intersect(&self, local_ray: Ray) -> Vec<Intersection<'_>> {
+        let mut intersections = Vec::new();
+        self.local_intersect_into(local_ray, &mut intersections);
+        intersections
+    }
+
+    fn local_intersect_into<'s>(
+        &'s self,
+        local_ray: Ray,
+        intersections: &mut Vec<Intersection<'s>>,
+    ) {
+        if let Some((t, u, v)) = intersect_triangle(self.p1, self.e1, self.e2, local_ray) {
+            intersections.push(Intersection::with_uv(t, self, u, v));
+        }
+    }
+
+    /// Interpolates the normal with the `u` and `v` of the hit.
+    fn normal_at_hit(&self, point: Point, hit: &Intersection) -> Vector {
+        match (hit.u, hit.v) {
+            (Some(u), Some(v)) => self.normal_at_uv(point, u, v),
+            _ => self.normal_at(point),
+        }
+    }
+
+    fn local_normal_at_uv(&self, _local_point: Point, u: f64, v: f64) -> Vector {
+        self.n2 * u + self.n3 * v + self.n1 * (1.0 - u - v)
+    }
+
+    /// Without the hit's `u` and `v`, they are recovered from the
+    /// position of the point within the plane of the triangle.
+    fn local_normal_at(&self, local_point: Point) -> Vector {
+        let p1_to_point = local_point - self.p1;
+        let d00 = self.e1.dot(self.e1);
+        let d01 = self.e1.dot(self.e2);
+        let d11 = self.e2.dot(self.e2);
+        let d20 = p1_to_point.dot(self.e1);
+        let d21 = p1_to_point.dot(self.e2);
+        let denominator = d00 * d11 - d01 * d01;
+        let u = (d11 * d20 - d01 * d21) / denominator;
+        let v = (d00 * d21 - d01 * d20) / denominator;
+        self.local_normal_at_uv(local_point, u, v)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::empty();
+        bounds.add_point(self.p1);
+        bounds.add_point(self.p2);
+        bounds.add_point(self.p3);
+        bounds
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn set_parent_transform(&mut self, transform: Mat4) {
+        self.transforms.set_parent(transform);
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn label_mut(&mut self) -> Option<&mut Option<String>> {
+        Some(&mut self.label)
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    fn debug(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::is_equal;
+    use crate::{point, vector};
+
+    #[test]
+    fn test_local_normal_at() {
+        // Finding the normal on a triangle
+        let triangle = Triangle::new(point![0, 1, 0], point![-1, 0, 0], point![1, 0, 0]);
+        assert_eq!(triangle.local_normal_at(point![0, 0.5, 0]), triangle.normal);
+        assert_eq!(
+            triangle.local_normal_at(point![-0.5, 0.75, 0]),
+            triangle.normal
+        );
+        assert_eq!(
+            triangle.local_normal_at(point![0.5, 0.25, 0]),
+            triangle.normal
+        );
+    }
+
+    #[test]
+    fn test_local_intersect() {
+        let triangle = Triangle::new(point![0, 1, 0], point![-1, 0, 0], point![1, 0, 0]);
+
+        // Intersecting a ray parallel to the triangle
+        let ray = Ray {
+            origin: point![0, -1, -2],
+            direction: vector![0, 1, 0],
+        };
+        assert!(triangle.local_intersect(ray).is_empty());
+
+        // A ray misses the p1-p3 edge
+        let ray = Ray {
+            origin: point![1, 1, -2],
+            direction: vector![0, 0, 1],
+        };
+        assert!(triangle.local_intersect(ray).is_empty());
+
+        // A ray misses the p1-p2 edge
+        let ray = Ray {
+            origin: point![-1, 1, -2],
+            direction: vector![0, 0, 1],
+        };
+        assert!(triangle.local_intersect(ray).is_empty());
+
+        // A ray misses the p2-p3 edge
+        let ray = Ray {
+            origin: point![0, -1, -2],
+            direction: vector![0, 0, 1],
+        };
+        assert!(triangle.local_intersect(ray).is_empty());
+
+        // A ray strikes a triangle
+        let ray = Ray {
+            origin: point![0, 0.5, -2],
+            direction: vector![0, 0, 1],
+        };
+        let intersections = triangle.local_intersect(ray);
+        assert_eq!(intersections.len(), 1);
+        assert!(is_equal(intersections[0].t, 2.0));
+    }
+
+    fn smooth_triangle() -> SmoothTriangle {
+        SmoothTriangle::new(
+            point![0, 1, 0],
+            point![-1, 0, 0],
+            point![1, 0, 0],
+            vector![0, 1, 0],
+            vector![-1, 0, 0],
+            vector![1, 0, 0],
+        )
+    }
+
+    #[test]
+    fn test_smooth_local_intersect() {
+        // An intersection with a smooth triangle stores u/v
+        let triangle = smooth_triangle();
+        let ray = Ray {
+            origin: point![-0.2, 0.3, -2],
+            direction: vector![0, 0, 1],
+        };
+        let intersections = triangle.local_intersect(ray);
+        assert_eq!(intersections.len(), 1);
+        assert!(is_equal(intersections[0].u.unwrap(), 0.45));
+        assert!(is_equal(intersections[0].v.unwrap(), 0.25));
+    }
+
+    #[test]
+    fn test_smooth_local_normal_at() {
+        // A smooth triangle uses u/v to interpolate the normal
+        let triangle = smooth_triangle();
+        let intersection = Intersection::with_uv(1.0, &triangle, 0.45, 0.25);
+        assert_eq!(
+            triangle.normal_at_uv(point![0, 0, 0], 0.45, 0.25),
+            vector![-0.5547, 0.83205, 0]
+        );
+
+        // Preparing the normal on a smooth triangle
+        let ray = Ray {
+            origin: point![-0.2, 0.3, -2],
+            direction: vector![0, 0, 1],
+        };
+        let comps = intersection.prepare(ray);
+        assert_eq!(comps.normal, vector![-0.5547, 0.83205, 0]);
+
+        // The hit gives the smooth normal where a flat
+        // triangle of the same vertices has a single one
+        let flat = Triangle::new(triangle.p1, triangle.p2, triangle.p3);
+        let point = point![-0.2, 0.3, 0];
+        let hit = &triangle.intersect(ray)[0];
+        let smooth_normal = triangle.normal_at_hit(point, hit);
+        assert_eq!(smooth_normal, vector![-0.5547, 0.83205, 0]);
+        assert_eq!(
+            flat.normal_at_hit(point, &flat.intersect(ray)[0]),
+            vector![0, 0, -1]
+        );
+        assert_ne!(smooth_normal, flat.normal_at(point));
+        assert_eq!(hit.prepare(ray).normal, smooth_normal);
+
+        // The interpolated normal at the centroid is the average of the vertex normals
+        let triangle = smooth_triangle();
+        let centroid = point![0, 1.0 / 3.0, 0];
+        let average = (triangle.n1 + triangle.n2 + triangle.n3) / 3;
+        assert_eq!(
+            triangle.local_normal_at_uv(centroid, 1.0 / 3.0, 1.0 / 3.0),
+            average
+        );
+        assert_eq!(triangle.local_normal_at(centroid), average);
+    }
+
+    #[test]
+    fn test_bounds() {
+        // A triangle has a bounding box enclosing its vertices
+        let triangle = Triangle::new(point![-3, 7, 2], point![6, 2, -4], point![2, -1, -1]);
+        let bounds = triangle.bounds();
+        assert_eq!(bounds.min, point![-3, -1, -4]);
+        assert_eq!(bounds.max, point![6, 7, 2]);
+    }
+}
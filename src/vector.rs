@@ -3,6 +3,7 @@ use std::ops::{Add, Div, Mul, Neg, Sub};
 
 /// Vector in 3D space
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector {
     pub x: f64,
     pub y: f64,
@@ -55,11 +56,8 @@ impl Vector {
     pub fn normalize(&self) -> Self {
         let magnitude = self.magnitude();
         if is_equal(magnitude, 0.0) {
-            eprintln!(
-                "\x1b[1;33mwarning\x1b[0m: {}\n",
-                "normalizing zero magnitude vector"
-            );
-            return self.clone();
+            eprintln!("\x1b[1;33mwarning\x1b[0m: normalizing zero magnitude vector\n");
+            return *self;
         }
         Self {
             x: self.x / magnitude,
@@ -68,6 +66,23 @@ impl Vector {
         }
     }
 
+    /// Same as `normalize`, without the warning for zero magnitude
+    /// vectors, for code that expects them and handles them itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// assert_eq!(vector![0, 3, 4].normalize_silent(), vector![0, 0.6, 0.8]);
+    /// assert_eq!(Vector::zero().normalize_silent(), Vector::zero());
+    /// ```
+    pub fn normalize_silent(&self) -> Self {
+        if is_equal(self.magnitude(), 0.0) {
+            return *self;
+        }
+        self.normalize()
+    }
+
     /// Dot product of two vectors.
     pub fn dot(&self, rhs: Self) -> f64 {
         self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
@@ -85,6 +100,54 @@ impl Vector {
     pub fn reflect(self, rhs: Self) -> Self {
         self - rhs * 2 * self.dot(rhs)
     }
+
+    /// Angle between two vectors, in radians from 0 to PI.
+    /// The cosine is clamped, so nearly parallel vectors
+    /// give 0 or PI rather than NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// # use std::f64::consts::FRAC_PI_2;
+    /// assert!(is_equal(vector![1, 0, 0].angle_between(vector![0, 2, 0]), FRAC_PI_2));
+    /// ```
+    pub fn angle_between(&self, other: Self) -> f64 {
+        let cosine = self.dot(other) / (self.magnitude() * other.magnitude());
+        cosine.clamp(-1.0, 1.0).acos()
+    }
+
+    /// Linear interpolation, `self` at `t` = 0 and `other` at `t` = 1.
+    /// `t` isn't clamped, values outside of [0, 1] extrapolate.
+    pub fn lerp(&self, other: Self, t: f64) -> Self {
+        *self + (other - *self) * t
+    }
+
+    /// Component of the vector along `axis`,
+    /// which doesn't have to be normalized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let vector = vector![1, 1, 0];
+    /// assert_eq!(vector.project_onto(vector![2, 0, 0]), vector![1, 0, 0]);
+    /// assert_eq!(vector.reject_from(vector![2, 0, 0]), vector![0, 1, 0]);
+    /// ```
+    pub fn project_onto(&self, axis: Self) -> Self {
+        let length_squared = axis.dot(axis);
+        if is_equal(length_squared, 0.0) {
+            eprintln!("\x1b[1;33mwarning\x1b[0m: projecting onto zero magnitude vector\n");
+            return Self::zero();
+        }
+        axis * (self.dot(axis) / length_squared)
+    }
+
+    /// Component of the vector perpendicular to `axis`,
+    /// what remains after removing `project_onto(axis)`.
+    pub fn reject_from(&self, axis: Self) -> Self {
+        *self - self.project_onto(axis)
+    }
 }
 
 impl PartialEq for Vector {
@@ -164,7 +227,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::f64::consts::SQRT_2;
+    use std::f64::consts::{FRAC_PI_4, PI, SQRT_2};
 
     #[test]
     fn test_magnitude() {
@@ -188,6 +251,11 @@ mod tests {
 
         // Normalizing a zero magnitude vector is the zero vector
         assert_eq!(vector![0, 0, 0].normalize(), vector![0, 0, 0]);
+
+        // with or without the warning
+        for vector in [vector![0, 0, 0], vector![1, 2, 3], vector![-4, 0, 0]] {
+            assert_eq!(vector.normalize_silent(), vector.normalize());
+        }
     }
 
     #[test]
@@ -212,6 +280,72 @@ mod tests {
         let vector = vector![0, -1, 0];
         let normal = vector![SQRT_2 / 2.0, SQRT_2 / 2.0, 0];
         assert_eq!(vector.reflect(normal), vector![1, 0, 0]);
+
+        // Reflecting keeps the magnitude and the angle with the normal
+        let vector = vector![0.3, -2, 1.4];
+        let normal = vector![1, 2, 0.5].normalize();
+        let reflected = vector.reflect(normal);
+        assert!(is_equal(reflected.magnitude(), vector.magnitude()));
+        assert!(is_equal(
+            reflected.angle_between(normal),
+            (-vector).angle_between(normal)
+        ));
+    }
+
+    #[test]
+    fn test_angle_between() {
+        assert!(is_equal(
+            vector![1, 0, 0].angle_between(vector![1, 1, 0]),
+            FRAC_PI_4
+        ));
+        assert!(is_equal(
+            vector![1, 0, 0].angle_between(vector![-3, 0, 0]),
+            PI
+        ));
+
+        // A vector makes no angle with itself, even when rounding
+        // pushes the cosine slightly past 1
+        for vector in [
+            vector![0.1, 0.2, 0.3],
+            vector![1e-3, 7, -1e5],
+            vector![1, 1, 1],
+        ] {
+            let angle = vector.angle_between(vector);
+            assert!(!angle.is_nan());
+            assert!(is_equal(angle, 0.0));
+            assert!(is_equal(vector.angle_between(-vector), PI));
+        }
+    }
+
+    #[test]
+    fn test_lerp() {
+        let (a, b) = (vector![1, -2, 3], vector![3, 2, -1]);
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 0.5), vector![2, 0, 1]);
+        assert_eq!(a.lerp(b, 1.0), b);
+    }
+
+    #[test]
+    fn test_project_onto() {
+        // Decomposing a vector along the x axis
+        let vector = vector![1, 1, 0];
+        let axis = vector![1, 0, 0];
+        assert_eq!(vector.project_onto(axis), vector![1, 0, 0]);
+        assert_eq!(vector.reject_from(axis), vector![0, 1, 0]);
+
+        // The parts sum back to the vector, the rejection
+        // perpendicular to the axis
+        let vector = vector![3, -2, 5];
+        let axis = vector![1, 2, -0.5];
+        let projection = vector.project_onto(axis);
+        let rejection = vector.reject_from(axis);
+        assert_eq!(projection + rejection, vector);
+        assert!(is_equal(rejection.dot(axis), 0.0));
+        assert!(is_equal(projection.cross(axis).magnitude(), 0.0));
+
+        // Projecting onto a zero magnitude vector is the zero vector
+        assert_eq!(vector.project_onto(Vector::zero()), Vector::zero());
+        assert_eq!(vector.reject_from(Vector::zero()), vector);
     }
 
     #[test]
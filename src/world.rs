@@ -1,55 +1,90 @@
 use crate::color::Color;
 use crate::intersection::Computation;
 use crate::intersection::Intersection;
-use crate::light::Light;
+use crate::light::{shading_rng, Light};
 use crate::mat4::Mat4;
 use crate::material::Material;
 use crate::point::Point;
+use crate::prelude::{is_equal, EPSILON};
 use crate::ray::Ray;
 use crate::shape::Shape;
 use crate::sphere::Sphere;
+use crate::stats;
+use crate::vector::Vector;
+use rand::Rng;
 
+/// With the `serde` feature, worlds can be serialized as long as
+/// every object is a `Sphere` or a `Plane`, each tagged with its type.
+///
+/// Cloning a world clones each of its objects with `Shape::clone_box`,
+/// so objects keep their ids in the clone.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct World {
-    pub light: Light,
+    pub lights: Vec<Light>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_objects",
+            deserialize_with = "deserialize_objects"
+        )
+    )]
     pub objects: Vec<Box<dyn Shape>>,
+    /// Color of rays escaping the world without hitting anything.
+    pub background: Color,
+    /// Number of rays cast around the normal of each shaded point,
+    /// darkening its ambient term by the fraction of them hitting an
+    /// object, 0 to leave the ambient term as is.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ambient_occlusion_samples: usize,
+    /// Length of the ambient occlusion rays, objects further
+    /// away from the shaded point don't darken it.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "default_ambient_occlusion_distance")
+    )]
+    pub ambient_occlusion_distance: f64,
+}
+
+#[cfg(feature = "serde")]
+fn default_ambient_occlusion_distance() -> f64 {
+    1.0
 }
 
 impl Default for World {
     fn default() -> Self {
-        let light = Light {
-            position: Point {
+        let light = Light::point(
+            Point {
                 x: -10.0,
                 y: 10.0,
                 z: -10.0,
             },
-            intensity: Color {
+            Color {
                 red: 1.0,
                 green: 1.0,
                 blue: 1.0,
             },
-        };
+        );
 
-        let sphere_outer = Sphere {
-            material: Material {
-                color: Color {
-                    red: 0.8,
-                    green: 1.0,
-                    blue: 0.6,
-                },
-                diffuse: 0.7,
-                specular: 0.2,
-                ..Default::default()
+        let mut sphere_outer = Sphere::new();
+        sphere_outer.material = Material {
+            color: Color {
+                red: 0.8,
+                green: 1.0,
+                blue: 0.6,
             },
+            diffuse: 0.7,
+            specular: 0.2,
             ..Default::default()
         };
-        let sphere_inner = Sphere {
-            transform: Mat4::identity().scale(0.5, 0.5, 0.5),
-            ..Default::default()
-        };
+        let sphere_inner = Sphere::new().set_transform(Mat4::identity().uniform_scale(0.5));
 
         Self {
-            light,
+            lights: vec![light],
             objects: vec![Box::new(sphere_outer), Box::new(sphere_inner)],
+            background: Color::BLACK,
+            ambient_occlusion_samples: 0,
+            ambient_occlusion_distance: 1.0,
         }
     }
 }
@@ -57,11 +92,35 @@ impl Default for World {
 impl World {
     pub fn new(light: Light) -> Self {
         Self {
-            light,
+            lights: vec![light],
             objects: Vec::new(),
+            background: Color::BLACK,
+            ambient_occlusion_samples: 0,
+            ambient_occlusion_distance: 1.0,
         }
     }
 
+    /// Starts an empty world, with no lights or objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let world = World::builder()
+    ///     .with_light(Light::point(point![-10, 10, -10], color![1, 1, 1]))
+    ///     .with_light(Light::point(point![10, 10, -10], color![0.2, 0.2, 0.4]))
+    ///     .with_object(Plane::new())
+    ///     .with_object(Sphere::at(point![0, 1, 0], 1.0))
+    ///     .with_object(Cube::new().set_transform(Mat4::identity().translate(3, 1, 0)))
+    ///     .with_background(color![0.1, 0.1, 0.2])
+    ///     .build();
+    /// assert_eq!(world.lights.len(), 2);
+    /// assert_eq!(world.objects.len(), 3);
+    /// ```
+    pub fn builder() -> WorldBuilder {
+        WorldBuilder::default()
+    }
+
     pub fn push<T>(&mut self, object: T)
     where
         T: Shape + 'static,
@@ -69,30 +128,123 @@ impl World {
         self.objects.push(Box::new(object));
     }
 
+    pub fn add_light(&mut self, light: Light) {
+        self.lights.push(light);
+    }
+
     /// Intersects a world with a ray.
     /// Returned vector of intersections is sorted.
-    pub fn intersect(&self, ray: Ray) -> Vec<Intersection> {
+    pub fn intersect(&self, ray: Ray) -> Vec<Intersection<'_>> {
+        self.intersect_where(ray, |_| true)
+    }
+
+    /// Intersects only the objects of a world matching `predicate`.
+    /// Returned vector of intersections is sorted.
+    pub fn intersect_where<P>(&self, ray: Ray, predicate: P) -> Vec<Intersection<'_>>
+    where
+        P: Fn(&dyn Shape) -> bool,
+    {
         let mut intersections = Vec::new();
+        self.intersect_where_into(ray, predicate, &mut intersections);
+        intersections
+    }
+
+    /// Same as `intersect`, clearing and refilling `intersections`
+    /// rather than allocating a new vector, so a render loop
+    /// can reuse the same buffer for every ray.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// # use trace::ray::Ray;
+    /// let world = World::default();
+    /// let mut buffer = Vec::new();
+    /// for y in [-2, 0, 2] {
+    ///     let ray = Ray {
+    ///         origin: point![0, y, -5],
+    ///         direction: vector![0, 0, 1],
+    ///     };
+    ///     world.intersect_into(ray, &mut buffer);
+    ///     assert_eq!(buffer.len(), world.intersect(ray).len());
+    /// }
+    /// ```
+    pub fn intersect_into<'w>(&'w self, ray: Ray, intersections: &mut Vec<Intersection<'w>>) {
+        self.intersect_where_into(ray, |_| true, intersections);
+    }
+
+    /// Same as `intersect`, also returning the index of the hit within
+    /// the sorted intersections, as `Intersection::hit` would find it,
+    /// `None` when every intersection is behind the ray.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// # use trace::ray::Ray;
+    /// let world = World::default();
+    /// let ray = Ray {
+    ///     origin: point![0, 0, 0],
+    ///     direction: vector![0, 0, 1],
+    /// };
+    /// let (intersections, hit) = world.intersections_and_hit(ray);
+    /// assert_eq!(intersections.len(), 4);
+    /// assert_eq!(hit, Some(2));
+    /// assert_eq!(intersections[2].t, 0.5);
+    /// ```
+    pub fn intersections_and_hit(&self, ray: Ray) -> (Vec<Intersection<'_>>, Option<usize>) {
+        let intersections = self.intersect(ray);
+        // sorted, so the hit is the first intersection in front
+        let hit = intersections
+            .iter()
+            .position(|intersection| intersection.t > 0.0 || is_equal(intersection.t, 0.0));
+        (intersections, hit)
+    }
+
+    fn intersect_where_into<'w, P>(
+        &'w self,
+        ray: Ray,
+        predicate: P,
+        intersections: &mut Vec<Intersection<'w>>,
+    ) where
+        P: Fn(&dyn Shape) -> bool,
+    {
+        intersections.clear();
         for object in &self.objects {
-            for intersection in object.intersect(ray) {
-                intersections.push(intersection);
+            if !predicate(object.as_ref()) {
+                continue;
             }
+            object.intersect_into(ray, intersections);
         }
         intersections.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        intersections
     }
 
-    pub fn is_shadowed(&self, point: Point) -> bool {
-        let point_to_light = self.light.position - point;
-        let distance = point_to_light.magnitude();
-        let direction = point_to_light.normalize();
+    /// Checks whether an object lies between `point`
+    /// and a light at `light_position`.
+    pub fn is_shadowed(&self, light_position: Point, point: Point) -> bool {
+        let point_to_light = light_position - point;
+        self.is_shadowed_towards(
+            point,
+            point_to_light.normalize(),
+            point_to_light.magnitude(),
+        )
+    }
+
+    /// Checks whether an object lies within `distance` of `point`
+    /// along `direction`, which must be normalized. With an infinite
+    /// distance, as for directional lights, any object along the
+    /// way casts a shadow. Objects whose material doesn't cast
+    /// shadows are skipped.
+    pub fn is_shadowed_towards(&self, point: Point, direction: Vector, distance: f64) -> bool {
+        stats::count(|stats| stats.shadow_rays += 1);
         let ray = Ray {
             origin: point,
             direction,
         };
+        // filtering the hits rather than the objects of the world
+        // also skips the children of groups that cast no shadow
         let intersections = self.intersect(ray);
-        let hit = Intersection::hit(&intersections);
-        if let Some(hit) = hit {
+        if let Some(hit) = Intersection::hit_shadow(&intersections) {
             if hit.t < distance {
                 return true;
             }
@@ -100,40 +252,290 @@ impl World {
         false
     }
 
+    /// Sums the contribution of each light of the world.
+    /// A world without lights shows only the ambient term
+    /// of each material, as under a white light.
+    ///
+    /// With ambient occlusion, the ambient term is darkened
+    /// by the fraction returned by `World::ambient_occlusion_at`.
     pub fn shade_hit(&self, comps: Computation) -> Color {
-        let shadowed = self.is_shadowed(comps.over_point);
-        comps.object.material().lighting(
-            &*comps.object,
-            self.light,
-            comps.over_point,
-            comps.eyev,
-            comps.normal,
-            shadowed,
-        )
+        let material = comps.object.material();
+        let occlusion = self.ambient_occlusion_at(comps.over_point, comps.normal);
+        if self.lights.is_empty() {
+            return material.ambient_at(comps.object, comps.over_point) * (1.0 - occlusion);
+        }
+        let mut color = Color::BLACK;
+        for &light in &self.lights {
+            let intensity = light.intensity_at(comps.over_point, self);
+            color += material.lighting_occluded(
+                comps.object,
+                light,
+                comps.over_point,
+                comps.eyev,
+                comps.normal,
+                intensity,
+                occlusion,
+            );
+        }
+        color
     }
 
+    /// Returns the fraction of `ambient_occlusion_samples` rays, cast from
+    /// `point` in random directions around `normal`, hitting an object
+    /// within `ambient_occlusion_distance`, 0 when there are no samples.
+    /// Objects that don't cast shadows don't occlude either.
+    ///
+    /// The directions are seeded by `point`, like the jitter of area
+    /// lights, so renders don't change from one run to the next.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// let world = World::builder()
+    ///     .with_object(Plane::new())
+    ///     .with_object(Sphere::at(point![0, 1, 0], 1.0))
+    ///     .with_ambient_occlusion(16, 1.0)
+    ///     .build();
+    /// let normal = vector![0, 1, 0];
+    /// // Points where the sphere meets the floor are darkened,
+    /// assert!(world.ambient_occlusion_at(point![0.5, 0.0001, 0], normal) > 0.0);
+    /// // points far from it are not
+    /// assert_eq!(world.ambient_occlusion_at(point![5, 0.0001, 0], normal), 0.0);
+    /// ```
+    pub fn ambient_occlusion_at(&self, point: Point, normal: Vector) -> f64 {
+        if self.ambient_occlusion_samples == 0 {
+            return 0.0;
+        }
+        let mut rng = shading_rng(point);
+        let mut occluded = 0;
+        for _ in 0..self.ambient_occlusion_samples {
+            // uniform over the unit sphere, then
+            // flipped into the hemisphere of the normal
+            let direction = loop {
+                let offset = Vector {
+                    x: rng.gen_range(-1.0..1.0),
+                    y: rng.gen_range(-1.0..1.0),
+                    z: rng.gen_range(-1.0..1.0),
+                };
+                let length_squared = offset.dot(offset);
+                if length_squared <= 1.0 && length_squared > EPSILON {
+                    break offset.normalize();
+                }
+            };
+            let direction = if direction.dot(normal) < 0.0 {
+                -direction
+            } else {
+                direction
+            };
+            let ray = Ray {
+                origin: point,
+                direction,
+            };
+            let intersections = self.intersect(ray);
+            if let Some(hit) = Intersection::hit_shadow(&intersections) {
+                if hit.t < self.ambient_occlusion_distance {
+                    occluded += 1;
+                }
+            }
+        }
+        occluded as f64 / self.ambient_occlusion_samples as f64
+    }
+
+    /// Checks the world for setup mistakes before rendering,
+    /// returning a description of every problem found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use trace::prelude::*;
+    /// assert!(World::default().validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        if self.lights.is_empty() {
+            problems.push("world has no lights".to_string());
+        }
+        let mut ids = std::collections::HashSet::new();
+        for object in &self.objects {
+            let id = object.id();
+            if !ids.insert(id) {
+                problems.push(format!("object {} shares its id with another object", id));
+            }
+            let transform = object.transform();
+            if transform.is_nan() {
+                problems.push(format!("object {} has NaN in its transform", id));
+            } else if !transform.is_invertible() {
+                problems.push(format!("object {} has a singular transform", id));
+            }
+            let material = object.material();
+            for (name, value) in [
+                ("ambient", material.ambient),
+                ("diffuse", material.diffuse),
+                ("specular", material.specular),
+            ] {
+                if !(0.0..=1.0).contains(&value) {
+                    problems.push(format!(
+                        "object {} has {} {} out of range [0, 1]",
+                        id, name, value
+                    ));
+                }
+            }
+            if material.shininess.is_nan() || material.shininess <= 0.0 {
+                problems.push(format!(
+                    "object {} has non-positive shininess {}",
+                    id, material.shininess
+                ));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Returns the color seen along `ray`, or the background
+    /// when it hits nothing. Without lights, surfaces are
+    /// shaded by their ambient term alone, see `shade_hit`.
     pub fn color_at(&self, ray: Ray) -> Color {
-        let intersections = self.intersect(ray);
-        let hit = Intersection::hit(&intersections);
-        match hit {
-            Some(hit) => self.shade_hit(hit.prepare(ray)),
-            None => Color {
-                red: 0.0,
-                green: 0.0,
-                blue: 0.0,
+        self.trace(ray).unwrap_or(self.background)
+    }
+
+    /// Same as `color_at`, returning `None` when the ray hits nothing.
+    pub fn trace(&self, ray: Ray) -> Option<Color> {
+        self.trace_into(ray, &mut Vec::new())
+    }
+
+    /// Same as `trace`, reusing `buffer` for the intersections
+    /// of the ray, see `intersect_into`.
+    pub fn trace_into<'w>(&'w self, ray: Ray, buffer: &mut Vec<Intersection<'w>>) -> Option<Color> {
+        self.intersect_into(ray, buffer);
+        let hit = Intersection::hit(buffer)?;
+        Some(self.shade_hit(hit.prepare(ray)))
+    }
+}
+
+/// Builds a `World` one light and object at a time, see `World::builder`.
+pub struct WorldBuilder {
+    world: World,
+}
+
+impl Default for WorldBuilder {
+    fn default() -> Self {
+        Self {
+            world: World {
+                lights: Vec::new(),
+                objects: Vec::new(),
+                background: Color::BLACK,
+                ambient_occlusion_samples: 0,
+                ambient_occlusion_distance: 1.0,
             },
         }
     }
 }
 
+impl WorldBuilder {
+    pub fn with_light(mut self, light: Light) -> Self {
+        self.world.add_light(light);
+
+        self
+    }
+
+    pub fn with_object<T>(mut self, object: T) -> Self
+    where
+        T: Shape + 'static,
+    {
+        self.world.push(object);
+
+        self
+    }
+
+    pub fn with_background(mut self, background: Color) -> Self {
+        self.world.background = background;
+
+        self
+    }
+
+    pub fn with_ambient_occlusion(mut self, samples: usize, distance: f64) -> Self {
+        self.world.ambient_occlusion_samples = samples;
+        self.world.ambient_occlusion_distance = distance;
+
+        self
+    }
+
+    pub fn build(self) -> World {
+        self.world
+    }
+}
+
+/// A shape tagged with its type, as returned by `Shape::serializable`.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+pub enum SerializableShape<'a> {
+    Sphere(&'a Sphere),
+    Plane(&'a crate::plane::Plane),
+}
+
+/// Owned counterpart of `SerializableShape`, for deserializing.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+enum DeserializedShape {
+    Sphere(Sphere),
+    Plane(crate::plane::Plane),
+}
+
+#[cfg(feature = "serde")]
+fn serialize_objects<S>(objects: &[Box<dyn Shape>], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    use serde::ser::{Error, SerializeSeq};
+    let mut seq = serializer.serialize_seq(Some(objects.len()))?;
+    for object in objects {
+        match object.serializable() {
+            Some(shape) => seq.serialize_element(&shape)?,
+            None => {
+                return Err(S::Error::custom(format!(
+                    "object {} can't be serialized",
+                    object.id()
+                )))
+            }
+        }
+    }
+    seq.end()
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_objects<'de, D>(deserializer: D) -> Result<Vec<Box<dyn Shape>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let shapes: Vec<DeserializedShape> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(shapes
+        .into_iter()
+        .map(|shape| -> Box<dyn Shape> {
+            match shape {
+                DeserializedShape::Sphere(sphere) => Box::new(sphere),
+                DeserializedShape::Plane(plane) => Box::new(plane),
+            }
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::camera::Camera;
     use crate::color;
+    use crate::group::Group;
     use crate::intersection::Intersection;
+    use crate::light::{AreaLight, DirectionalLight};
+    use crate::pattern::stripe::Stripe;
+    use crate::plane::Plane;
     use crate::point;
-    use crate::prelude::is_equal;
     use crate::ray::Ray;
+    use crate::shape::ShapeBuilder;
     use crate::{vector, vector::Vector};
 
     #[test]
@@ -157,29 +559,195 @@ mod tests {
         assert!(is_equal(intersections[1].t, 4.5));
         assert!(is_equal(intersections[2].t, 5.5));
         assert!(is_equal(intersections[3].t, 6.0));
+
+        // Intersecting into a buffer gives the same sorted intersections,
+        // dropping whatever the buffer held before
+        let sphere = Sphere::new();
+        let mut buffer = vec![Intersection::new(-1.0, &sphere)];
+        world.intersect_into(ray, &mut buffer);
+        assert_eq!(buffer, intersections);
+        let miss = Ray {
+            origin: point![0, 5, -5],
+            direction: vector![0, 0, 1],
+        };
+        world.intersect_into(miss, &mut buffer);
+        assert!(buffer.is_empty());
+
+        // A shape with NaN in its transform is skipped
+        let mut world = World::default();
+        world.push(Sphere::new().set_transform(Mat4::identity().translate(f64::NAN, 0, 0)));
+        let intersections = world.intersect(ray);
+        assert_eq!(intersections.len(), 4);
+        assert!(intersections.iter().all(|i| !i.t.is_nan()));
+    }
+
+    #[test]
+    fn test_intersections_and_hit() {
+        // The hit index points to the same intersection as Intersection::hit
+        let world = World::default();
+        for z in [-5.0, -0.75, 0.0, 0.75, 5.0] {
+            let ray = Ray {
+                origin: point![0, 0, z],
+                direction: vector![0, 0, 1],
+            };
+            let (intersections, hit) = world.intersections_and_hit(ray);
+            assert_eq!(intersections, world.intersect(ray));
+            assert_eq!(
+                hit.map(|index| &intersections[index]),
+                Intersection::hit(&intersections)
+            );
+        }
+
+        // Beyond the spheres there is no hit, though there are intersections
+        let ray = Ray {
+            origin: point![0, 0, 5],
+            direction: vector![0, 0, 1],
+        };
+        let (intersections, hit) = world.intersections_and_hit(ray);
+        assert_eq!(intersections.len(), 4);
+        assert_eq!(hit, None);
+    }
+
+    #[test]
+    fn test_intersect_where() {
+        // Filtering to one object yields only that object's intersections
+        let world = World::default();
+        let ray = Ray {
+            origin: point![0, 0, -5],
+            direction: vector![0, 0, 1],
+        };
+        let inner_id = world.objects[1].id();
+        let intersections = world.intersect_where(ray, |object| object.id() == inner_id);
+        assert_eq!(intersections.len(), 2);
+        assert!(is_equal(intersections[0].t, 4.5));
+        assert!(is_equal(intersections[1].t, 5.5));
+        assert!(intersections
+            .iter()
+            .all(|intersection| intersection.object.id() == inner_id));
+
+        // Filtering out every object yields no intersections
+        let intersections = world.intersect_where(ray, |_| false);
+        assert!(intersections.is_empty());
     }
 
     #[test]
     fn test_is_shadowed() {
         // There is no shadow when nothing is collinear with point and light
         let world = World::default();
+        let light = point![-10, 10, -10];
         let point = point![0, 10, 0];
-        assert_eq!(world.is_shadowed(point), false);
+        assert!(!world.is_shadowed(light, point));
 
         // The shadow when an object is between the point and the light
         let world = World::default();
+        let light = point![-10, 10, -10];
         let point = point![10, -10, 10];
-        assert_eq!(world.is_shadowed(point), true);
+        assert!(world.is_shadowed(light, point));
 
         // There is no shadow when an object is behind the light
         let world = World::default();
+        let light = point![-10, 10, -10];
         let point = point![-20, 20, -20];
-        assert_eq!(world.is_shadowed(point), false);
+        assert!(!world.is_shadowed(light, point));
 
         // There is no shadow when an object is behind the point
         let world = World::default();
+        let light = point![-10, 10, -10];
         let point = point![-2, 2, -2];
-        assert_eq!(world.is_shadowed(point), false);
+        assert!(!world.is_shadowed(light, point));
+
+        // A directional light is shadowed by objects at any distance
+        let mut world = World::new(Light::Directional(DirectionalLight {
+            direction: vector![0, -1, 0],
+            intensity: color![1, 1, 1],
+        }));
+        world.push(Sphere::new().set_transform(Mat4::identity().translate(0, 1e6, 0)));
+        let light = world.lights[0];
+        assert_eq!(light.intensity_at(point![0, 0, 0], &world), 0.0);
+        assert_eq!(light.intensity_at(point![2, 0, 0], &world), 1.0);
+
+        // while a point light is only shadowed by objects in front of it
+        let light = Light::point(point![0, 10, 0], color![1, 1, 1]);
+        assert_eq!(light.intensity_at(point![0, 0, 0], &world), 1.0);
+
+        // An object that doesn't cast shadows leaves none on a plane,
+        // but is still hit by rays
+        let mut world = World::new(Light::point(point![0, 10, 0], color![1, 1, 1]));
+        let mut sphere = Sphere::new().set_transform(Mat4::identity().translate(0, 3, 0));
+        sphere.material.casts_shadow = false;
+        world.push(sphere);
+        world.push(Plane::new());
+        assert!(!world.is_shadowed(point![0, 10, 0], point![0, 0.001, 0]));
+        let ray = Ray {
+            origin: point![0, 10, 0],
+            direction: vector![0, -1, 0],
+        };
+        let intersections = world.intersect(ray);
+        assert_eq!(intersections.len(), 3);
+        assert_eq!(intersections[0].object.id(), world.objects[0].id());
+
+        // Objects that don't cast shadows are skipped within groups too
+        let mut world = World::new(Light::point(point![0, 10, 0], color![1, 1, 1]));
+        let mut group = Group::new();
+        let mut sphere = Sphere::new().set_transform(Mat4::identity().translate(0, 3, 0));
+        sphere.material.casts_shadow = false;
+        group.push(sphere);
+        world.push(group);
+        assert!(!world.is_shadowed(point![0, 10, 0], point![0, 0.001, 0]));
+    }
+
+    #[test]
+    fn test_penumbra() {
+        // An area light casts a soft shadow, fading from
+        // full shadow under a sphere to full light away from it
+        let light = Light::Area(AreaLight::new(
+            point![-1, 5, -1],
+            vector![2, 0, 0],
+            8,
+            vector![0, 0, 2],
+            8,
+            color![1, 1, 1],
+        ));
+        let mut world = World::new(light);
+        world.push(Sphere::new());
+        let intensities: Vec<f64> = (0..=16)
+            .map(|x| light.intensity_at(point![x as f64 * 0.25, -2, 0], &world))
+            .collect();
+        assert_eq!(intensities[0], 0.0);
+        assert_eq!(intensities[16], 1.0);
+        assert!(intensities.windows(2).all(|pair| pair[0] <= pair[1]));
+        let penumbra = intensities
+            .iter()
+            .filter(|&&intensity| intensity > 0.0 && intensity < 1.0)
+            .count();
+        assert!(penumbra >= 3);
+
+        // A point light casts a hard shadow instead
+        let light = Light::point(point![0, 5, 0], color![1, 1, 1]);
+        world.lights = vec![light];
+        assert!((0..=16).all(|x| {
+            let intensity = light.intensity_at(point![x as f64 * 0.25, -2, 0], &world);
+            intensity == 0.0 || intensity == 1.0
+        }));
+
+        // as does a single sample of a soft point light
+        let soft = Light::soft_point(point![0, 5, 0], color![1, 1, 1], 0.5, 1);
+        assert!((0..=16).all(|x| {
+            let point = point![x as f64 * 0.25, -2, 0];
+            soft.intensity_at(point, &world) == light.intensity_at(point, &world)
+        }));
+
+        // Sampling it several times softens the edge of the shadow
+        let light = Light::soft_point(point![0, 5, 0], color![1, 1, 1], 0.5, 16);
+        world.lights = vec![light];
+        let intensities: Vec<f64> = (0..=16)
+            .map(|x| light.intensity_at(point![x as f64 * 0.25, -2, 0], &world))
+            .collect();
+        assert_eq!(intensities[0], 0.0);
+        assert_eq!(intensities[16], 1.0);
+        assert!(intensities
+            .iter()
+            .any(|&intensity| intensity > 0.0 && intensity < 1.0));
     }
 
     #[test]
@@ -192,6 +760,13 @@ mod tests {
         };
         assert_eq!(world.color_at(ray), color![0, 0, 0]);
 
+        // The color when a ray misses a world with a background
+        let world = World {
+            background: color![0.5, 0.7, 1.0],
+            ..Default::default()
+        };
+        assert_eq!(world.color_at(ray), color![0.5, 0.7, 1.0]);
+
         // The color when a ray hits
         let world = World::default();
         let ray = Ray {
@@ -221,50 +796,267 @@ mod tests {
             direction: vector![0, 0, 1],
         };
         let shape = &(*world.objects[0]);
-        let intersection = Intersection {
-            t: 4.0,
-            object: shape,
-        };
+        let intersection = Intersection::new(4.0, shape);
         let comps = intersection.prepare(ray);
         assert_eq!(world.shade_hit(comps), color![0.38066, 0.47583, 0.2855]);
 
         // Shading an intersection from the inside
-        let mut world = World::default();
-        world.light = Light {
-            position: point![0, 0.25, 0],
-            intensity: color![1, 1, 1],
+        let world = World {
+            lights: vec![Light::point(point![0, 0.25, 0], color![1, 1, 1])],
+            ..Default::default()
         };
         let ray = Ray {
             origin: point![0, 0, 0],
             direction: vector![0, 0, 1],
         };
-        let intersection = Intersection {
-            t: 0.5,
-            object: &(*world.objects[1]),
-        };
+        let intersection = Intersection::new(0.5, &(*world.objects[1]));
         let comps = intersection.prepare(ray);
         assert_eq!(world.shade_hit(comps), color![0.90498, 0.90498, 0.90498]);
 
         // shade_hit() is given an intersection in shadow
-        let mut world = World::default();
-        world.light = Light {
-            position: point![0, 0, -10],
-            intensity: color![1, 1, 1],
+        let mut world = World {
+            lights: vec![Light::point(point![0, 0, -10], color![1, 1, 1])],
+            ..Default::default()
         };
         let sphere_one = Sphere::new();
         world.objects.push(Box::new(sphere_one));
         let mut sphere_two = Sphere::new();
-        sphere_two.transform = Mat4::identity().translate(0, 0, 10);
+        sphere_two = sphere_two.set_transform(Mat4::identity().translate(0, 0, 10));
         world.objects.push(Box::new(sphere_two));
         let ray = Ray {
             origin: point![0, 0, 5],
             direction: vector![0, 0, 1],
         };
-        let intersection = Intersection {
-            t: 4.0,
-            object: &(*world.objects[1]),
-        };
+        let intersection = Intersection::new(4.0, &(*world.objects[1]));
         let comps = intersection.prepare(ray);
         assert_eq!(world.shade_hit(comps), color![0.1, 0.1, 0.1]);
+
+        // Shading a striped sphere shows its stripes
+        let mut world = World::new(Light::point(point![0, 0, -10], color![1, 1, 1]));
+        let mut sphere = Sphere::new().set_pattern(Box::new(Stripe::default()));
+        sphere.material.ambient = 1.0;
+        sphere.material.diffuse = 0.0;
+        sphere.material.specular = 0.0;
+        world.push(sphere);
+        for (x, color) in [(0.5, Color::WHITE), (-0.5, Color::BLACK)] {
+            let ray = Ray {
+                origin: point![x, 0, -5],
+                direction: vector![0, 0, 1],
+            };
+            let intersections = world.intersect(ray);
+            let comps = intersections[0].prepare(ray);
+            assert_eq!(world.shade_hit(comps), color);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        // A world and a camera rebuilt from JSON render the same image
+        let world = World::default();
+        let mut camera = Camera::new(11, 11, std::f64::consts::PI / 2.0);
+        camera.transform =
+            Mat4::identity().view_transform(point![0, 0, -5], point![0, 0, 0], vector![0, 1, 0]);
+        let json = serde_json::to_string(&(&world, &camera)).unwrap();
+        let (result, result_camera): (World, Camera) = serde_json::from_str(&json).unwrap();
+        assert_eq!(result.lights, world.lights);
+        assert_eq!(result.objects.len(), 2);
+        assert_ne!(result.objects[0].id(), world.objects[0].id());
+        assert_eq!(result.objects[1].transform(), world.objects[1].transform());
+        let image = camera.render(&world);
+        let result_image = result_camera.render(&result);
+        for y in 0..image.height {
+            for x in 0..image.width {
+                assert_eq!(result_image[(x, y)], image[(x, y)]);
+            }
+        }
+
+        // Shapes with no serialized form are reported
+        let mut world = World::default();
+        world.push(Group::new());
+        assert!(serde_json::to_string(&world).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        // A world with no lights and a zero-scale object
+        let mut world = World {
+            lights: Vec::new(),
+            objects: Vec::new(),
+            background: Color::BLACK,
+            ambient_occlusion_samples: 0,
+            ambient_occlusion_distance: 1.0,
+        };
+        world.push(Sphere::new().set_transform(Mat4::identity().uniform_scale(0)));
+        let problems = world.validate().unwrap_err();
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("no lights"));
+        assert!(problems[1].contains("singular"));
+
+        // A small but invertible scale is valid
+        let mut world = World::default();
+        world.push(Sphere::with_radius(0.02));
+        assert!(world.validate().is_ok());
+
+        // A material field out of range
+        let mut world = World::default();
+        world.objects[0].material_mut().diffuse = 1.5;
+        let problems = world.validate().unwrap_err();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("diffuse"));
+
+        // NaN in a transform
+        let mut world = World::default();
+        world.push(Sphere::new().set_transform(Mat4::identity().translate(f64::NAN, 0, 0)));
+        let problems = world.validate().unwrap_err();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("NaN"));
+
+        // The same object added twice
+        let mut world = World::default();
+        let copy = world.objects[1].clone();
+        world.objects.push(copy);
+        let problems = world.validate().unwrap_err();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("shares its id"));
+    }
+
+    #[test]
+    fn test_clone() {
+        // A cloned world can be changed without touching the original
+        let world = World::default();
+        let mut variant = world.clone();
+        variant.lights[0] = Light::point(point![10, 10, -10], color![1, 1, 1]);
+        variant.objects[0].material_mut().color = color![1, 0, 0];
+        assert_eq!(world.lights[0], World::default().lights[0]);
+        assert_eq!(world.objects[0].material().color, color![0.8, 1.0, 0.6]);
+
+        // Objects keep their ids and everything else
+        for (object, clone) in world.objects.iter().zip(&variant.objects) {
+            assert_eq!(object.id(), clone.id());
+            assert_eq!(object.transform(), clone.transform());
+        }
+        assert_eq!(variant.objects[1].material(), world.objects[1].material());
+
+        // Patterns and nested shapes are cloned too
+        let mut group = Group::new();
+        group.push(Sphere::new().set_pattern(Box::new(Stripe::default())));
+        let mut world = World::default();
+        world.push(group);
+        let variant = world.clone();
+        let ray = Ray {
+            origin: point![0, 0, -5],
+            direction: vector![0, 0, 1],
+        };
+        assert_eq!(variant.color_at(ray), world.color_at(ray));
+    }
+
+    #[test]
+    fn test_builder() {
+        // A built world matches one assembled by hand
+        let first = Light::point(point![-10, 10, -10], color![1, 1, 1]);
+        let second = Light::point(point![10, 10, -10], color![0.5, 0.5, 0.5]);
+        let built = World::builder()
+            .with_light(first)
+            .with_light(second)
+            .with_object(Sphere::new())
+            .with_object(Plane::new())
+            .with_object(Sphere::at(point![2, 0, 0], 0.5))
+            .build();
+        let mut world = World::new(first);
+        world.add_light(second);
+        world.push(Sphere::new());
+        world.push(Plane::new());
+        world.push(Sphere::at(point![2, 0, 0], 0.5));
+        assert_eq!(built.lights, world.lights);
+        assert_eq!(built.objects.len(), world.objects.len());
+        assert_eq!(built.background, Color::BLACK);
+        let ray = Ray {
+            origin: point![0, 0, -5],
+            direction: vector![0, 0, 1],
+        };
+        assert_eq!(built.color_at(ray), world.color_at(ray));
+
+        // An added light brightens the scene
+        let mut world = World::default();
+        let before = world.color_at(ray);
+        world.add_light(second);
+        let after = world.color_at(ray);
+        assert!(after.red > before.red && after.green > before.green);
+    }
+
+    #[test]
+    fn test_no_lights() {
+        // A world without lights renders flat ambient colors
+        let mut world = World::default();
+        world.lights.clear();
+        let mut camera = Camera::new(11, 11, std::f64::consts::PI / 2.0);
+        camera.transform =
+            Mat4::identity().view_transform(point![0, 0, -5], point![0, 0, 0], vector![0, 1, 0]);
+        let image = camera.render(&world);
+        let ambient = color![0.8, 1.0, 0.6] * 0.1;
+        assert_eq!(image[(5, 5)], ambient);
+        for y in 0..camera.vsize {
+            for x in 0..camera.hsize {
+                if image.alpha((x, y)) > 0.0 {
+                    assert_eq!(image[(x, y)], ambient);
+                } else {
+                    assert_eq!(image[(x, y)], Color::BLACK);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ambient_occlusion() {
+        // Ambient occlusion darkens the floor where a sphere sits on it,
+        // fading out away from the sphere
+        let mut world = World::builder()
+            .with_object(Plane::new())
+            .with_object(Sphere::at(point![0, 1, 0], 1.0))
+            .with_ambient_occlusion(64, 1.0)
+            .build();
+        let up = vector![0, 1, 0];
+        let occlusions: Vec<f64> = [0.25, 0.75, 1.5, 3.0]
+            .iter()
+            .map(|&x| world.ambient_occlusion_at(point![x, EPSILON, 0], up))
+            .collect();
+        assert!(occlusions[0] > 0.0);
+        assert!(occlusions.windows(2).all(|pair| pair[0] >= pair[1]));
+        assert_eq!(occlusions[3], 0.0);
+
+        // only the ambient term is darkened, under any number of lights
+        let ray = Ray {
+            origin: point![0.25, 5, -5],
+            direction: (point![0.25, 0, 0] - point![0.25, 5, -5]).normalize(),
+        };
+        let shade = |world: &World| {
+            let intersections = world.intersect(ray);
+            let hit = Intersection::hit(&intersections).unwrap();
+            let comps = hit.prepare(ray);
+            let occlusion = world.ambient_occlusion_at(comps.over_point, comps.normal);
+            (world.shade_hit(comps), occlusion)
+        };
+        let (color, occlusion) = shade(&world);
+        assert_eq!(color, Color::WHITE * 0.1 * (1.0 - occlusion));
+        world.add_light(Light::point(point![-10, 10, -10], color![0.5, 0.5, 0.5]));
+        world.add_light(Light::point(point![10, 10, -10], color![0.5, 0.5, 0.5]));
+        let (occluded, _) = shade(&world);
+        world.ambient_occlusion_samples = 0;
+        let (unoccluded, _) = shade(&world);
+        assert_eq!(occluded, unoccluded - Color::WHITE * 0.1 * occlusion);
+
+        // Objects that cast no shadow don't occlude
+        let mut sphere = Sphere::at(point![0, 1, 0], 1.0);
+        sphere.material.casts_shadow = false;
+        let world = World::builder()
+            .with_object(Plane::new())
+            .with_object(sphere)
+            .with_ambient_occlusion(64, 1.0)
+            .build();
+        assert_eq!(
+            world.ambient_occlusion_at(point![0.25, EPSILON, 0], up),
+            0.0
+        );
     }
 }